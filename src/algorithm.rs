@@ -3,12 +3,12 @@ use std::{
     fmt::Debug,
     mem,
     ops::{Add, AddAssign, Div, Mul, Not, Rem, Sub, SubAssign},
-    sync::atomic::{self, AtomicUsize},
+    sync::atomic::{self, AtomicBool, AtomicUsize},
 };
 
 use crate::{
     armor::Armor, charms::Charm, decorations::Decoration, requirements::Requirement,
-    skillpoint::SkillPoint,
+    skill_id::SkillId, skillpoint::SkillPoint,
 };
 use itertools::Itertools;
 use quick_cache::sync::Cache;
@@ -56,7 +56,7 @@ pub fn scores(requirements: &[Requirement], decorations: &[Decoration]) -> Vec<f
         .map(|requirement| {
             decorations
                 .iter()
-                .map(|dec| dec.points(&requirement.name) as f64 / dec.slots() as f64)
+                .map(|dec| dec.points(requirement.id) as f64 / dec.slots() as f64)
                 .max_by(f64::total_cmp)
                 .unwrap_or(0.0)
         })
@@ -103,7 +103,7 @@ where
                 .extract_if(.., |(_, part)| {
                     requirements
                         .iter()
-                        .all(|req| part.points(&req.name) == current_part.points(&req.name))
+                        .all(|req| part.points(req.id) == current_part.points(req.id))
                         && part.slots() == current_part.slots()
                 })
                 .max_by_key(|(_, p)| p.defence())
@@ -113,7 +113,7 @@ where
             parts_vec.retain_mut(|(_, part)| {
                 requirements
                     .iter()
-                    .any(|req| part.points(&req.name) > current_part.points(&req.name))
+                    .any(|req| part.points(req.id) > current_part.points(req.id))
                     || part.slots() > current_part.slots()
             });
             parts_vec.push(best);
@@ -125,8 +125,8 @@ where
 
     for &pair in requirements.iter().enumerate().flat_map(|(idx, req)| {
         parts_vec.iter().max_by(|(_, a), (_, b)| {
-            (a.slots() as f64 * scores[idx] + a.points(&req.name) as f64)
-                .total_cmp(&(b.slots() as f64 * scores[idx] + b.points(&req.name) as f64))
+            (a.slots() as f64 * scores[idx] + a.points(req.id) as f64)
+                .total_cmp(&(b.slots() as f64 * scores[idx] + b.points(req.id) as f64))
         })
     }) {
         trimmed.insert(pair);
@@ -134,7 +134,7 @@ where
     for pair in parts_vec.clone().into_iter().sorted_by_key(|(_, part)| {
         let mut score = part.slots() as f64;
         for (req_idx, req) in requirements.iter().enumerate() {
-            score += part.points(&req.name) as f64 / scores[req_idx];
+            score += part.points(req.id) as f64 / scores[req_idx];
         }
         (score * 16.0) as i32
     }) {
@@ -172,7 +172,7 @@ where
         currentpieces.push(piece.slots());
 
         for req in requirements {
-            let skillpoints = piece.points(&req.name);
+            let skillpoints = piece.points(req.id);
             currentpieces.push(skillpoints);
             score += skillpoints as f64;
         }
@@ -265,6 +265,287 @@ pub struct FindResult {
     pub decorations_count_indices: Vec<(usize, usize)>,
 }
 
+/// Decorations that fill the missing skill points of an armor combination, kept between searches.
+/// Entries only depend on the points that are still missing and on the decorations that can fill
+/// them, so other searches that miss the same points, like the ones for additional skills, use them
+/// too.
+pub struct DecorationCache {
+    cache: Cache<DecorationCacheKey, Option<Vec<CachedDecorations>>>,
+}
+
+/// Slots by size of the combination and the skills that still matter, sorted by id
+#[derive(PartialEq, Eq, Hash, Clone)]
+struct DecorationCacheKey {
+    slots: [u32; 3],
+    skills: Vec<CachedSkill>,
+}
+
+/// A skill of a `DecorationCacheKey` and what the decorations of the search can do for it
+#[derive(PartialEq, Eq, Hash, Clone)]
+struct CachedSkill {
+    id: SkillId,
+    points: i32,
+    /// Points of the decoration used for each slot size
+    summands: [i32; 3],
+    /// Bits of the best points per slot
+    score: u64,
+    /// Skill the decorations of this skill take points from
+    reduces: Option<SkillId>,
+}
+
+/// Count, skill and slot size of decorations, the decoration itself is looked up in the search
+type CachedDecorations = (usize, SkillId, i32);
+
+/// Hit rate of the `DecorationCache`, shown in the debug panel
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DecorationCacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub len: usize,
+    pub capacity: u64,
+}
+
+impl DecorationCacheStats {
+    pub fn hit_rate(&self) -> f64 {
+        let lookups = self.hits + self.misses;
+        if lookups == 0 {
+            0.0
+        } else {
+            self.hits as f64 / lookups as f64
+        }
+    }
+}
+
+impl DecorationCache {
+    pub fn new(capacity: usize) -> Self {
+        DecorationCache {
+            cache: Cache::new(capacity),
+        }
+    }
+    pub fn stats(&self) -> DecorationCacheStats {
+        DecorationCacheStats {
+            hits: self.cache.hits(),
+            misses: self.cache.misses(),
+            len: self.cache.len(),
+            capacity: self.cache.capacity(),
+        }
+    }
+    pub fn clear(&self) {
+        self.cache.clear();
+    }
+}
+
+impl Default for DecorationCache {
+    fn default() -> Self {
+        DecorationCache::new(100_000)
+    }
+}
+
+const MAXDEPTH: usize = 4;
+
+/// Slots by size, skill points still missing and piece indices of an armor combination
+type Candidate = ([u32; 3], Vec<i32>, [i32; MAXDEPTH + 2]);
+
+/// The pieces of an armor search, used for the search with every body piece
+struct ArmorSearch<'a> {
+    /// charms, head, arms, waist, legs
+    pieces: &'a [Vec<&'a [i32]>],
+    req_points: &'a [i32],
+    max_score_remaining: &'a [i32],
+    max_possible_score_per_slot: f64,
+    weapon_slots: usize,
+    torso_up_allowed: [bool; 4],
+    max_torso_up: usize,
+}
+
+impl<'a> ArmorSearch<'a> {
+    fn candidates(&'a self, current_body_part: &'a [i32]) -> Candidates<'a> {
+        let req_count = self.req_points.len();
+        let mut req_points = self.req_points.to_vec();
+        for pos in 0..req_count {
+            req_points[pos] -= current_body_part[pos + 2];
+        }
+        req_points.resize(req_count * (MAXDEPTH + 2), 0);
+        //last element is slots for body piece
+        let mut slots_per_piece = [self.weapon_slots; MAXDEPTH + 3];
+        slots_per_piece[6] = current_body_part[1] as usize;
+        Candidates {
+            search: self,
+            current_body_part,
+            req_points,
+            depth: 0,
+            min_depth: 0,
+            idx: [0; MAXDEPTH + 2],
+            slots_per_piece,
+            slots_total: [0; MAXDEPTH + 1],
+            indices: [current_body_part[0]; MAXDEPTH + 2],
+            difficulty: [0; MAXDEPTH + 2],
+            torso_up_count: [0; MAXDEPTH + 2],
+            finished: false,
+        }
+    }
+    /// Only the combinations that start with the pieces at the indices of `prefix`.
+    /// The index after the last piece stands for torso up.
+    fn candidates_with_prefix(
+        &'a self,
+        current_body_part: &'a [i32],
+        prefix: &[usize],
+    ) -> Candidates<'a> {
+        let mut candidates = self.candidates(current_body_part);
+        for &index in prefix {
+            candidates.idx[candidates.depth] = index;
+            match candidates.choice() {
+                Some((piece, piece_index))
+                    if !candidates.skip_rest(piece) && candidates.place(piece, piece_index) => {}
+                _ => {
+                    candidates.finished = true;
+                    break;
+                }
+            }
+        }
+        candidates.min_depth = candidates.depth;
+        candidates
+    }
+}
+
+/// Depth first search over the other pieces for one body piece.
+/// The combinations are generated when they are needed, so they never have to be kept in memory.
+struct Candidates<'a> {
+    search: &'a ArmorSearch<'a>,
+    current_body_part: &'a [i32],
+    req_points: Vec<i32>,
+    depth: usize,
+    /// Depth of the first piece that isn't fixed by a prefix
+    min_depth: usize,
+    idx: [usize; MAXDEPTH + 2],
+    slots_per_piece: [usize; MAXDEPTH + 3],
+    slots_total: [i32; MAXDEPTH + 1],
+    indices: [i32; MAXDEPTH + 2],
+    difficulty: [i32; MAXDEPTH + 2],
+    //torso up pieces before depth
+    torso_up_count: [usize; MAXDEPTH + 2],
+    finished: bool,
+}
+
+impl<'a> Candidates<'a> {
+    /// The piece at the current position and its index, -1 for torso up
+    fn choice(&self) -> Option<(&'a [i32], i32)> {
+        let depth = self.depth;
+        let pieces: &'a [Vec<&'a [i32]>] = self.search.pieces;
+        if self.idx[depth] == pieces[depth].len()
+            && depth != 0
+            && self.search.torso_up_allowed[depth - 1]
+            && self.torso_up_count[depth] < self.search.max_torso_up
+        {
+            //charms don't
+            //have torso up
+            Some((self.current_body_part, -1))
+        } else {
+            pieces[depth]
+                .get(self.idx[depth])
+                .map(|&current_piece| (current_piece, current_piece[0]))
+        }
+    }
+    /// Pieces are sorted by score,
+    /// if this one can't reach the requirements the next ones can't either
+    fn skip_rest(&self, current_piece: &[i32]) -> bool {
+        let depth = self.depth;
+        depth != 0
+            && self.difficulty[depth]
+                > current_piece[current_piece.len() - 1]
+                    + self.search.max_score_remaining[depth]
+                    + (self.slots_total[depth - 1] as f64 * self.search.max_possible_score_per_slot)
+                        .ceil() as i32
+            && self.idx[depth] < self.search.pieces[depth].len()
+    }
+    /// Adds the piece and goes one level deeper, unless the requirements can't be reached with it
+    fn place(&mut self, current_piece: &[i32], current_piece_index: i32) -> bool {
+        let depth = self.depth;
+        let req_count = self.search.req_points.len();
+        self.slots_per_piece[depth] = current_piece[1] as usize;
+        self.indices[depth] = current_piece_index;
+        self.slots_total[depth] = current_piece[1]
+            + if depth == 0 {
+                self.search.weapon_slots as i32
+            } else {
+                self.slots_total[depth - 1]
+            };
+
+        self.difficulty[depth + 1] = 0;
+        for i in 0..req_count {
+            let points = current_piece[i + 2];
+            let new_req_points = self.req_points[req_count * depth + i] - points;
+            self.req_points[req_count * (depth + 1) + i] = new_req_points;
+            self.difficulty[depth + 1] += new_req_points.max(0);
+        }
+
+        //if difficulty is higher than maximum possible remaining score, don't go deeper
+        if self.search.max_score_remaining[depth]
+            + (self.slots_total[depth] as f64 * self.search.max_possible_score_per_slot).ceil()
+                as i32
+            >= self.difficulty[depth + 1]
+        {
+            self.torso_up_count[depth + 1] =
+                self.torso_up_count[depth] + usize::from(current_piece_index == -1);
+            self.depth += 1;
+            self.idx[self.depth] = 0;
+            true
+        } else {
+            false
+        }
+    }
+}
+
+impl Iterator for Candidates<'_> {
+    type Item = Candidate;
+
+    fn next(&mut self) -> Option<Candidate> {
+        let req_count = self.search.req_points.len();
+        while !self.finished {
+            let depth = self.depth;
+            if depth > MAXDEPTH {
+                let mut slot_size_amount_available = [0; 3];
+                for slot in self.slots_per_piece {
+                    if slot > 0 {
+                        slot_size_amount_available[slot - 1] += 1;
+                    }
+                }
+                let final_req_points: Vec<_> = self
+                    .req_points
+                    .iter()
+                    .cloned()
+                    .skip(req_count * depth)
+                    .take(req_count)
+                    .collect();
+                self.depth -= 1;
+                self.idx[self.depth] += 1;
+                return Some((slot_size_amount_available, final_req_points, self.indices));
+            }
+            let Some((current_piece, current_piece_index)) = self.choice() else {
+                if depth == self.min_depth {
+                    self.finished = true;
+                } else {
+                    self.depth -= 1;
+                    self.idx[self.depth] += 1;
+                }
+                continue;
+            };
+
+            //pieces are sorted by score so we can skip to the end
+            if self.skip_rest(current_piece) {
+                self.idx[depth] = self.search.pieces[depth].len();
+                continue;
+            }
+
+            if !self.place(current_piece, current_piece_index) {
+                self.idx[depth] += 1;
+            }
+        }
+        None
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
 pub fn find(
     head: &[&Armor],
     body: &[&Armor],
@@ -275,7 +556,10 @@ pub fn find(
     decorations: &[&Decoration],
     requirements: &[Requirement],
     weapon_slots: usize,
+    torso_up_allowed: [bool; 4], // head, arms, waist, legs
+    max_torso_up: usize,
     max_results: usize,
+    decoration_cache: &DecorationCache,
 ) -> Vec<FindResult> {
     let chunksize = requirements.len() + 3;
     let mut allpieces = Vec::new();
@@ -292,7 +576,13 @@ pub fn find(
         .cloned()
         .max_by(f64::total_cmp)
         .unwrap_or_default();
-    let (decorations_grouped, attribute_order, decoration_summands, negatives_map) = {
+    let (
+        decorations_grouped,
+        attribute_order,
+        decoration_summands,
+        negatives_map,
+        negative_indices,
+    ) = {
         //choose a high value as default to have the least amount of unecessary calculations later
         let mut summands: Vec<[_; 3]> = vec![[127; 3]; requirements.len()];
         let mut chunked: Vec<_> = decorations_createvec.chunks_exact(chunksize).collect();
@@ -342,9 +632,56 @@ pub fn find(
             }
         }
         negatives_map.resize(requirements.len(), false);
-        let mut missing_indices = (0..requirements.len()).filter(|x|!order.contains(x)).collect_vec();
+        let mut missing_indices = (0..requirements.len())
+            .filter(|x| !order.contains(x))
+            .collect_vec();
         order.append(&mut missing_indices);
-        (grouped, order, summands, negatives_map)
+        (grouped, order, summands, negatives_map, negative_indices)
+    };
+    let cached_skills = requirements
+        .iter()
+        .enumerate()
+        .map(|(index, requirement)| CachedSkill {
+            id: requirement.id,
+            points: 0,
+            summands: decoration_summands[index],
+            score: scores[index].to_bits(),
+            reduces: negative_indices[index].map(|reduced| requirements[reduced].id),
+        })
+        .collect_vec();
+    let reduced = (0..requirements.len())
+        .map(|index| negative_indices.contains(&Some(index)))
+        .collect_vec();
+    let cache_key = |points: &[i32], slots: [u32; 3]| DecorationCacheKey {
+        slots,
+        //reached skills don't change which decorations fit,
+        //unless decorations take points from them
+        skills: cached_skills
+            .iter()
+            .zip(points)
+            .enumerate()
+            .filter(|&(index, (skill, &points))| {
+                points > 0 || skill.reduces.is_some() || reduced[index]
+            })
+            .map(|(_, (skill, &points))| CachedSkill {
+                points,
+                ..skill.clone()
+            })
+            .sorted_by_key(|skill| skill.id)
+            .collect(),
+    };
+    let decoration_indices = |decorations: Vec<CachedDecorations>| {
+        decorations
+            .into_iter()
+            .filter_map(|(count, id, slots)| {
+                let attribute = requirements.iter().position(|req| req.id == id)?;
+                decorations_grouped[attribute]
+                    .iter()
+                    .rev() //get high value
+                    .find(|dec| dec[1] == slots)
+                    .map(|decoration| (count, decoration[0] as usize))
+            })
+            .collect_vec()
     };
     let check_decorations = |final_req_points_original: &Vec<i32>, slots_at_size: &[u32; 3]| {
         let mut final_req_points = final_req_points_original.clone();
@@ -378,13 +715,11 @@ pub fn find(
                         .zip(slot_counts)
                         .filter(|(&count, _slots)| count > 0)
                     {
-                        if let Some(decoration) = decorations_grouped[attribute]
-                            .iter()
-                            .rev() //get high value
-                            .find(|dec| dec[1] == slots)
-                        {
-                            result_decorations.push((count as usize, decoration[0] as usize));
-                        }
+                        result_decorations.push((
+                            count as usize,
+                            requirements[attribute].id,
+                            slots,
+                        ));
                     }
                 }
                 return Some(result_decorations);
@@ -436,7 +771,7 @@ pub fn find(
             }
         }
     };
-    let decoration_cache = Cache::new(3000);
+    let decoration_cache = &decoration_cache.cache;
     {
         let (pieces, score) = createvec(charms, requirements, &scores);
         allpieces.push(pieces);
@@ -480,105 +815,30 @@ pub fn find(
         .collect_vec();
     let req_points = requirements.iter().map(|req| req.points).collect_vec();
     let body_stripped = allpieces_chunked.pop().unwrap();
-    let req_count = req_points.len();
-    let mut final_check = Vec::new();
-    for current_body_part in body_stripped.iter() {
-        let mut req_points = req_points.clone();
-        for pos in 0..req_count {
-            req_points[pos] -= current_body_part[pos + 2];
-        }
-        const MAXDEPTH: usize = 4;
-        let mut idx = [0; MAXDEPTH + 2];
-        req_points.resize(req_count * (MAXDEPTH + 2), 0);
-        let mut depth = 0;
-        //last element is slots for body piece
-        let mut slots_per_piece = [weapon_slots; MAXDEPTH + 3];
-        slots_per_piece[6] = current_body_part[1] as usize;
-        let mut slots_total = [0; MAXDEPTH + 1];
-        let mut indices = [current_body_part[0]; MAXDEPTH + 2];
-        let mut difficulty = [0; MAXDEPTH + 2];
-        loop {
-            if depth > MAXDEPTH {
-                let mut slot_size_amount_available = [0; 3];
-                for slot in slots_per_piece {
-                    if slot > 0 {
-                        slot_size_amount_available[slot - 1] += 1;
-                    }
-                }
-                let final_req_points: Vec<_> = req_points
-                    .iter()
-                    .cloned()
-                    .skip(req_count * depth)
-                    .take(req_count)
-                    .collect();
-                final_check.push((slot_size_amount_available, final_req_points, indices));
-                depth -= 1;
-                idx[depth] += 1;
-                continue;
-            }
-            let (current_piece, current_piece_index) =
-                if idx[depth] == allpieces_chunked[depth].len() && depth != 0 {
-                    //charms don't
-                    //have torso up
-                    (*current_body_part, -1)
-                } else if idx[depth] >= allpieces_chunked[depth].len() {
-                    if depth == 0 {
-                        break;
-                    }
-                    depth -= 1;
-                    idx[depth] += 1;
-                    continue;
-                } else {
-                    let current_piece = allpieces_chunked[depth][idx[depth]];
-                    (current_piece, current_piece[0])
-                };
-
-            //pieces are sorted by score so we can skip to the end
-            if depth != 0
-                && difficulty[depth]
-                    > current_piece[current_piece.len() - 1]
-                        + max_score_remaining[depth]
-                        + (slots_total[depth - 1] as f64 * max_possible_score_per_slot).ceil()
-                            as i32
-                && idx[depth] < allpieces_chunked[depth].len()
-            {
-                idx[depth] = allpieces_chunked[depth].len();
-                continue;
-            }
-
-            slots_per_piece[depth] = current_piece[1] as usize;
-            indices[depth] = current_piece_index;
-            slots_total[depth] = current_piece[1]
-                + if depth == 0 {
-                    weapon_slots as i32
-                } else {
-                    slots_total[depth - 1]
-                };
-
-            difficulty[depth + 1] = 0;
-            for i in 0..req_count {
-                let points = current_piece[i + 2];
-                let new_req_points = req_points[req_count * depth + i] - points;
-                req_points[req_count * (depth + 1) + i] = new_req_points;
-                difficulty[depth + 1] += new_req_points.max(0);
-            }
-
-            //if difficulty is higher than maximum possible remaining score, don't go deeper
-            if max_score_remaining[depth]
-                + (slots_total[depth] as f64 * max_possible_score_per_slot).ceil() as i32
-                >= difficulty[depth + 1]
-            {
-                depth += 1;
-                idx[depth] = 0;
-            } else {
-                idx[depth] += 1;
-            }
-        }
-    }
+    let search = ArmorSearch {
+        pieces: &allpieces_chunked,
+        req_points: &req_points,
+        max_score_remaining: &max_score_remaining,
+        max_possible_score_per_slot,
+        weapon_slots,
+        torso_up_allowed,
+        max_torso_up,
+    };
+    // every worker searches the combinations with one body piece, charm and head at a time
+    let search = &search;
+    let charm_count = allpieces_chunked[0].len();
+    let head_count = allpieces_chunked[1].len();
+    let candidates = body_stripped.par_iter().flat_map(|current_body_part| {
+        (0..charm_count).into_par_iter().flat_map(move |charm| {
+            //the index after the last head is torso up
+            (0..=head_count).into_par_iter().flat_map_iter(move |head| {
+                search.candidates_with_prefix(current_body_part, &[charm, head])
+            })
+        })
+    });
 
     let result_count = AtomicUsize::new(0);
-    let result = final_check
-        .into_par_iter()
+    let result = candidates
         .filter_map(
             |(slot_size_amount_available, mut final_req_points, indices)| {
                 //stop if > desired amount of results
@@ -625,15 +885,6 @@ pub fn find(
                         continue;
                     }
                 }
-                let hash_calc = |points: &Vec<i32>, slots: [u32; 3]| {
-                    points
-                        .iter()
-                        .map(|&x| (x + 31) as u128)
-                        .chain(slots.iter().map(|&x| x as u128))
-                        .enumerate()
-                        .map(|(idx, value)| value << (6 * idx))
-                        .sum::<u128>()
-                };
                 let calculation_difficulty =
                     final_req_points.iter().filter(|&&p| p > 2).product::<i32>();
                 let use_cache = calculation_difficulty > 80;
@@ -652,13 +903,13 @@ pub fn find(
                 // final_req_points.hash(&mut hasher);
                 // slot_size_amount_available.hash(&mut hasher);
                 let (decorations_result, was_present) = if use_cache {
-                    let hash = hash_calc(&final_req_points, slot_size_amount_available);
-                    let result = decoration_cache.get(&hash);
+                    let key = cache_key(&final_req_points, slot_size_amount_available);
+                    let result = decoration_cache.get(&key);
                     let was_present = result.is_some();
                     let result = result.unwrap_or_else(|| {
                         let value =
                             check_decorations(&final_req_points, &slot_size_amount_available);
-                        decoration_cache.insert(hash, value.clone());
+                        decoration_cache.insert(key, value.clone());
                         value
                     });
                     (result, was_present)
@@ -669,9 +920,10 @@ pub fn find(
                     )
                 };
 
-                if !was_present && calculation_difficulty > 200 {
+                //if these slots aren't enough, fewer slots aren't enough either
+                if !was_present && decorations_result.is_none() && calculation_difficulty > 200 {
                     decoration_cache.insert(
-                        hash_calc(
+                        cache_key(
                             &final_req_points,
                             [
                                 slot_size_amount_available[0].saturating_sub(1),
@@ -683,7 +935,7 @@ pub fn find(
                     );
                     if slot_size_amount_available[1] > 0 {
                         decoration_cache.insert(
-                            hash_calc(
+                            cache_key(
                                 &final_req_points,
                                 [
                                     slot_size_amount_available[0],
@@ -694,7 +946,7 @@ pub fn find(
                             None,
                         );
                         decoration_cache.insert(
-                            hash_calc(
+                            cache_key(
                                 &final_req_points,
                                 [
                                     slot_size_amount_available[0] + 1,
@@ -705,7 +957,7 @@ pub fn find(
                             None,
                         );
                         decoration_cache.insert(
-                            hash_calc(
+                            cache_key(
                                 &final_req_points,
                                 [
                                     slot_size_amount_available[0] + 2,
@@ -718,7 +970,7 @@ pub fn find(
                     }
                     if slot_size_amount_available[2] > 0 {
                         decoration_cache.insert(
-                            hash_calc(
+                            cache_key(
                                 &final_req_points,
                                 [
                                     slot_size_amount_available[0] + 2,
@@ -729,7 +981,7 @@ pub fn find(
                             None,
                         );
                         decoration_cache.insert(
-                            hash_calc(
+                            cache_key(
                                 &final_req_points,
                                 [
                                     slot_size_amount_available[0] + 1,
@@ -742,6 +994,7 @@ pub fn find(
                     }
                 }
                 decorations_result.map(|ret_decorations| {
+                    let ret_decorations = decoration_indices(ret_decorations);
                     let body_index = indices[5] as usize;
                     let head_index = indices[1].try_into().ok();
                     let arms_index = indices[2].try_into().ok();
@@ -764,3 +1017,306 @@ pub fn find(
         .collect();
     result
 }
+
+/// Number of `Charm::cost` values `find_ideal_charm` tries before giving up.
+/// Every value is one full search, so this bounds how long it runs
+pub const MAX_IDEAL_CHARM_COSTS: usize = 16;
+
+/// Searches for the charm with the lowest `Charm::cost` among `candidates`
+/// that leads to at least one result.
+/// `candidates` have to be sorted by cost. Stops with `None` as soon as `cancelled` is set,
+/// `progress` gets the percentage of costs already checked
+#[allow(clippy::too_many_arguments)]
+pub fn find_ideal_charm(
+    head: &[&Armor],
+    body: &[&Armor],
+    arms: &[&Armor],
+    waist: &[&Armor],
+    legs: &[&Armor],
+    candidates: &[Charm],
+    decorations: &[&Decoration],
+    requirements: &[Requirement],
+    weapon_slots: usize,
+    torso_up_allowed: [bool; 4],
+    max_torso_up: usize,
+    decoration_cache: &DecorationCache,
+    cancelled: &AtomicBool,
+    mut progress: impl FnMut(f32),
+) -> Option<Charm> {
+    let groups = candidates
+        .iter()
+        .chunk_by(|charm| charm.cost())
+        .into_iter()
+        .map(|(_, group)| group.collect_vec())
+        .take(MAX_IDEAL_CHARM_COSTS)
+        .collect_vec();
+    for (index, charms) in groups.iter().enumerate() {
+        if cancelled.load(atomic::Ordering::Relaxed) {
+            return None;
+        }
+        progress((100.0 * index as f32) / groups.len() as f32);
+        if let Some(result) = find(
+            head,
+            body,
+            arms,
+            waist,
+            legs,
+            charms,
+            decorations,
+            requirements,
+            weapon_slots,
+            torso_up_allowed,
+            max_torso_up,
+            1,
+            decoration_cache,
+        )
+        .first()
+        {
+            return Some(charms[result.charms_index].clone());
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{skill::Skill, skill_id::SkillIds, skillpoint::SkillPoints};
+
+    // layout: index, slots, points, score. Sorted by score like in find
+    const CHARMS: &[[i32; 4]] = &[[0, 0, 3, 3], [1, 2, 0, 2]];
+    const HEAD: &[[i32; 4]] = &[[0, 1, 4, 5], [1, 0, 2, 2], [2, 0, 0, 0]];
+    const ARMS: &[[i32; 4]] = &[[0, 2, 2, 4], [1, 0, 1, 1]];
+    const WAIST: &[[i32; 4]] = &[[0, 0, 4, 4], [1, 3, 0, 3], [2, 1, 0, 1]];
+    const LEGS: &[[i32; 4]] = &[[0, 1, 3, 4], [1, 0, 0, 0]];
+    const BODY: [i32; 4] = [0, 1, 2, 3];
+
+    fn pieces() -> Vec<Vec<&'static [i32]>> {
+        [CHARMS, HEAD, ARMS, WAIST, LEGS]
+            .iter()
+            .map(|pieces| pieces.iter().map(|piece| &piece[..]).collect())
+            .collect()
+    }
+
+    /// Every combination without any pruning, in the order of the depth first search
+    fn serial(req_points: i32, weapon_slots: usize, max_torso_up: usize) -> Vec<Candidate> {
+        let options = [CHARMS, HEAD, ARMS, WAIST, LEGS]
+            .iter()
+            .enumerate()
+            .map(|(depth, pieces)| {
+                let mut options = pieces.iter().collect_vec();
+                if depth != 0 {
+                    options.push(&BODY);
+                }
+                options.into_iter().enumerate()
+            })
+            .multi_cartesian_product();
+        let mut candidates = Vec::new();
+        for combination in options {
+            let mut indices = [BODY[0]; MAXDEPTH + 2];
+            let mut torso_up_count = 0;
+            let mut slots = vec![weapon_slots as i32, BODY[1]];
+            let mut points = req_points - BODY[2];
+            for (depth, (option, piece)) in combination.into_iter().enumerate() {
+                let torso_up =
+                    depth != 0 && option == [CHARMS, HEAD, ARMS, WAIST, LEGS][depth].len();
+                indices[depth] = if torso_up { -1 } else { piece[0] };
+                torso_up_count += usize::from(torso_up);
+                slots.push(piece[1]);
+                points -= piece[2];
+            }
+            if torso_up_count > max_torso_up {
+                continue;
+            }
+            let mut slot_size_amount_available = [0; 3];
+            for slot in slots.into_iter().filter(|&slot| slot > 0) {
+                slot_size_amount_available[slot as usize - 1] += 1;
+            }
+            candidates.push((slot_size_amount_available, vec![points], indices));
+        }
+        candidates
+    }
+
+    #[test]
+    fn candidates_match_serial_traversal() {
+        let pieces = pieces();
+        let search = ArmorSearch {
+            pieces: &pieces,
+            req_points: &[3],
+            //high enough that nothing is pruned
+            max_score_remaining: &[100; MAXDEPTH + 1],
+            max_possible_score_per_slot: 1.0,
+            weapon_slots: 1,
+            torso_up_allowed: [true; 4],
+            max_torso_up: 1,
+        };
+        let candidates = search.candidates(&BODY).collect_vec();
+        assert_eq!(candidates, serial(3, 1, 1));
+    }
+
+    #[test]
+    fn torso_up_is_limited() {
+        let pieces = pieces();
+        let search = |torso_up_allowed, max_torso_up| {
+            ArmorSearch {
+                pieces: &pieces,
+                req_points: &[3],
+                max_score_remaining: &[100; MAXDEPTH + 1],
+                max_possible_score_per_slot: 1.0,
+                weapon_slots: 1,
+                torso_up_allowed,
+                max_torso_up,
+            }
+            .candidates(&BODY)
+            .collect_vec()
+        };
+        let torso_up_count = |(_, _, indices): &Candidate| {
+            indices[1..=4].iter().filter(|&&index| index == -1).count()
+        };
+        assert!(search([true; 4], 0)
+            .iter()
+            .all(|candidate| torso_up_count(candidate) == 0));
+        assert_eq!(search([true; 4], 0), serial(3, 1, 0));
+        assert_eq!(search([true; 4], 4), serial(3, 1, 4));
+        assert!(serial(3, 1, 4)
+            .iter()
+            .any(|candidate| torso_up_count(candidate) == 4));
+        //a pinned head can't be replaced by torso up
+        let pinned_head = search([false, true, true, true], 4);
+        assert!(!pinned_head.is_empty());
+        assert!(pinned_head.iter().all(|(_, _, indices)| indices[1] != -1));
+        assert_eq!(
+            pinned_head,
+            serial(3, 1, 4)
+                .into_iter()
+                .filter(|(_, _, indices)| indices[1] != -1)
+                .collect_vec()
+        );
+    }
+
+    #[test]
+    fn prefixes_cover_the_whole_search() {
+        let pieces = pieces();
+        let search = ArmorSearch {
+            pieces: &pieces,
+            req_points: &[14],
+            //suffix sums of the best scores like in find
+            max_score_remaining: &[20, 17, 12, 8, 4],
+            max_possible_score_per_slot: 1.0,
+            weapon_slots: 1,
+            torso_up_allowed: [true; 4],
+            max_torso_up: 2,
+        };
+        let candidates = search.candidates(&BODY).collect_vec();
+        let with_prefixes = (0..CHARMS.len())
+            .flat_map(|charm| (0..=HEAD.len()).map(move |head| [charm, head]))
+            .flat_map(|prefix| search.candidates_with_prefix(&BODY, &prefix).collect_vec())
+            .collect_vec();
+        assert!(!candidates.is_empty());
+        assert!(candidates.len() < serial(14, 1, 2).len());
+        assert_eq!(candidates, with_prefixes);
+        //pruning only drops combinations that can't reach the requirements
+        assert!(serial(14, 1, 2)
+            .iter()
+            .filter(|(slots, points, _)| {
+                points[0] <= (slots[0] + 2 * slots[1] + 3 * slots[2]) as i32
+            })
+            .all(|candidate| candidates.contains(candidate)));
+    }
+
+    /// Skills A to D, each with a three slot decoration worth 6 points
+    fn decorations() -> (SkillIds, Vec<Decoration>) {
+        let skills = ["A", "B", "C", "D"].map(|attribute| {
+            serde_json::from_value::<Skill>(serde_json::json!({ "name_attribute": attribute }))
+                .unwrap()
+        });
+        let ids = SkillIds::new(&skills);
+        let decorations = skills
+            .iter()
+            .map(|skill| {
+                let mut decoration: Decoration = serde_json::from_value(serde_json::json!({
+                    "name": skill.name_attribute,
+                    "slots": 3,
+                    "skill_1_name": skill.name_attribute,
+                    "skill_1_points": 6,
+                }))
+                .unwrap();
+                decoration.intern_skills(&ids);
+                decoration
+            })
+            .collect_vec();
+        (ids, decorations)
+    }
+
+    /// Searches sets with a three slot weapon, body and charm. The body has 10 points in D.
+    /// Only decorations of the required skills are used, like in the app.
+    fn search(
+        ids: &SkillIds,
+        decorations: &[Decoration],
+        skills: &[&str],
+        cache: &DecorationCache,
+    ) -> usize {
+        let requirements = skills
+            .iter()
+            .map(|&skill| Requirement {
+                name: skill.to_owned(),
+                points: if skill == "D" { 10 } else { 6 },
+                id: ids.get(skill),
+            })
+            .collect_vec();
+        let decorations = decorations
+            .iter()
+            .filter(|decoration| skills.contains(&decoration.name.as_str()))
+            .collect_vec();
+        let mut body = Armor {
+            slots: 3,
+            skills: vec![SkillPoints::new("D", 10)],
+            ..Default::default()
+        };
+        body.intern_skills(ids);
+        let piece = Armor::default();
+        let charm = Charm {
+            slots: 3,
+            ..Default::default()
+        };
+        find(
+            &[&piece],
+            &[&body],
+            &[&piece],
+            &[&piece],
+            &[&piece],
+            &[&charm],
+            &decorations,
+            &requirements,
+            3,
+            [false; 4],
+            0,
+            10,
+            cache,
+        )
+        .len()
+    }
+
+    /// One decoration of A, B and C fills each of the three slots
+    #[test]
+    fn cached_searches_find_the_same_sets() {
+        let (ids, decorations) = decorations();
+        let cache = DecorationCache::default();
+        assert_eq!(search(&ids, &decorations, &["A", "B", "C"], &cache), 1);
+        //a feasible set must not leave a failure for its own slots in the cache
+        assert_eq!(search(&ids, &decorations, &["A", "B", "C"], &cache), 1);
+    }
+
+    #[test]
+    fn searches_for_additional_skills_share_cache_entries() {
+        let (ids, decorations) = decorations();
+        let cache = DecorationCache::default();
+        search(&ids, &decorations, &["A", "B", "C"], &cache);
+        let base = cache.stats();
+        //the body already has D, so the same points are missing
+        assert_eq!(search(&ids, &decorations, &["A", "B", "C", "D"], &cache), 1);
+        let probe = cache.stats();
+        assert_eq!(probe.hits, base.hits + 1);
+        assert_eq!(probe.len, base.len);
+    }
+}
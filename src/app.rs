@@ -1,18 +1,23 @@
 use std::{
-    collections::HashMap,
+    collections::{HashMap, HashSet},
     fs::File,
-    io::{BufRead, BufReader},
-    iter,
+    io, iter,
     mem::take,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
 };
 
 use iced::{
     event, font,
     futures::{channel::mpsc, SinkExt, Stream},
+    keyboard::{self, key::Named},
     stream,
     widget::{
         button, checkbox, column, combo_box, container, horizontal_space, pick_list, progress_bar,
-        row, scrollable, slider, text, text_input, Column, Row,
+        row, scrollable, slider, text, text_input, tooltip, Column, Row,
     },
     window::{self},
     Element, Event, Length, Padding, Task, Theme,
@@ -23,15 +28,25 @@ use serde::{Deserialize, Serialize};
 use std::fmt::Write as _;
 
 use crate::{
-    algorithm::{find, scores, trim, FindResult},
+    algorithm::{find, find_ideal_charm, scores, trim, DecorationCache, FindResult},
     armor::Armor,
+    charm_list::{is_share_code, parse_charms, parse_share_code, share_code, skill_aliases},
     charms::Charm,
     component::Component,
     decorations::Decoration,
+    game::{Game, HunterRank},
+    loading::{
+        read_csv, read_lines, read_optional, read_required, read_skill_translation,
+        read_translation, LoadError,
+    },
+    paths::{data_file, language_file, user_file},
+    relics::{possible_relics, RELIC_DEFENCE, RELIC_PREFIX, RELIC_RESISTANCE},
     requirements::Requirement,
     segmented_button::segmented_button,
     skill::Skill,
-    skillpoint::SkillPoint,
+    skill_id::SkillIds,
+    skillpoint::{intern_skills, SkillPoint, SkillPoints},
+    strings::Strings,
     types::{ArmorType, GatheringHallRank, Language, Sex, WeaponType},
 };
 
@@ -51,25 +66,57 @@ fn contains_fuzzy(s: &str, pattern: &str) -> bool {
     true
 }
 
+/// Shows `description` when hovering `content`
+fn with_description<'a>(
+    content: impl Into<Element<'a, Message, Theme>>,
+    description: String,
+) -> Element<'a, Message, Theme> {
+    if description.is_empty() {
+        content.into()
+    } else {
+        tooltip(
+            content,
+            container(text(description).size(12))
+                .padding(5)
+                .max_width(300)
+                .style(container::bordered_box),
+            tooltip::Position::FollowCursor,
+        )
+        .into()
+    }
+}
+
 #[derive(Debug, Clone)]
 pub enum Message {
     Abort,
     EditCharms,
     EditRelics,
+    ImportCharms,
+    SetImportPath(String),
+    ImportLoad,
+    ExportShareCode,
+    ExportFile,
+    ExportFileOverwrite,
     PartExcludedRemoved(usize),
     CustomRemove(usize),
     CustomClear,
     CustomSave,
     CustomAdd,
+    CustomEdit(usize),
     PartExcludedAdded(String),
     PartsExcludedClear,
+    PartPinnedRemoved(usize),
+    PartPinned(Option<ArmorType>, String),
+    PartsPinnedClear,
     LanguageChanged(Language),
     ClearRequiredSkills,
     EventOccured(Event),
     SetsFound(FindResults),
     Search,
     SearchAdditionalSkills,
-    GatheringHallRankSelected(GatheringHallRank),
+    SearchIdealCharm,
+    IdealCharmCancelled,
+    HunterRankSelected(HunterRank),
     VillageRankSelected(i32),
     SexSelected(Sex),
     WeaponTypeSelected(WeaponType),
@@ -85,11 +132,25 @@ pub enum Message {
     FontLoaded(Result<(), font::Error>),
     TrimCountChanged(f64),
     SetSlots(i32),
-    SetProgress(f32),
+    SetProgress(Progress),
     SetMinRarity(i32),
-    SetProgressSender(mpsc::Sender<Option<f32>>),
+    PossibleRelicsToggled(bool),
+    TorsoUpToggled(bool),
+    MaxTorsoUpSelected(usize),
+    SetProgressSender(mpsc::Sender<Progress>),
     SetArmorName(String),
     SetArmorType(ArmorType),
+    SetRelicDefenceMin(i32),
+    SetRelicDefenceMax(i32),
+    SetRelicResistance(usize, i32),
+    PresetSelected(String),
+    PresetNameChanged(String),
+    PresetSave,
+    PresetDelete,
+    SkillTypeSelected(usize),
+    LoadErrorsDismissed,
+    NoticesDismissed,
+    DecorationCacheCleared,
 }
 struct Data {
     pub body: Vec<Armor>,
@@ -102,70 +163,90 @@ struct Data {
     pub relic_arms: Vec<Armor>,
     pub relic_waist: Vec<Armor>,
     pub relic_legs: Vec<Armor>,
+    /// Relic pieces that can drop, used for every armor type when enabled
+    pub possible_relics: Vec<Armor>,
     pub charms: Vec<Charm>,
     pub decorations: Vec<Decoration>,
     pub skills: Vec<Skill>,
+    /// Ids of the translated attributes, the same ids as the ones of the base data
+    pub skill_ids: SkillIds,
 }
 
 impl Data {
-    fn translate(language: Language, flags: &Flags) -> Data {
+    /// Searches use the parts followed by the owned relics and the possible relics,
+    /// results index into them
+    fn part(&self, armor_type: ArmorType, index: usize) -> &Armor {
+        let (parts, relics) = match armor_type {
+            ArmorType::Head => (&self.head, &self.relic_head),
+            ArmorType::Chest => (&self.body, &self.relic_body),
+            ArmorType::Arms => (&self.arms, &self.relic_arms),
+            ArmorType::Waist => (&self.waist, &self.relic_waist),
+            ArmorType::Legs => (&self.legs, &self.relic_legs),
+        };
+        parts
+            .iter()
+            .chain(relics)
+            .chain(&self.possible_relics)
+            .nth(index)
+            .unwrap()
+    }
+    fn translate(language: Language, flags: &Flags) -> Result<Data, LoadError> {
         let (attribute_translation_map, skills) = {
             let mut skills = flags.skills.clone();
-            let mut skill_attributes = Vec::new();
-            let mut seperator = false;
-            let mut skill_start_index = 0;
-            for (pos, skill_translation) in
-                BufReader::new(File::open(format!("./Languages/{language}/skills.txt")).unwrap())
-                    .lines()
-                    .enumerate()
-            {
-                if seperator {
-                    skills[pos - skill_start_index].name = skill_translation.unwrap();
-                } else if skill_translation.as_ref().is_ok_and(|x| !x.is_empty()) {
-                    skill_attributes.push(skill_translation.unwrap());
-                } else {
-                    seperator = true;
-                    skill_start_index = pos + 1;
-                }
-            }
-            let mut attribute_translation_map = HashMap::new();
-            for (attribute, translation) in skills
-                .iter_mut()
+            let attributes = skills
+                .iter()
                 .map(|s| s.name_attribute.clone())
                 .unique()
-                .zip(skill_attributes.into_iter())
-            {
-                attribute_translation_map.insert(attribute, translation);
+                .collect_vec();
+            //the last attribute is the one of torso up
+            let (skill_attributes, skill_names) = read_skill_translation(
+                &language_file(language, "skills.txt"),
+                attributes.len() + 1,
+                skills.len(),
+            )?;
+            for (skill, name) in skills.iter_mut().zip(skill_names) {
+                skill.name = name;
             }
+            let attribute_translation_map: HashMap<String, String> =
+                attributes.into_iter().zip(skill_attributes).collect();
             for skill in skills.iter_mut() {
                 skill
                     .name_attribute
                     .clone_from(&attribute_translation_map[&skill.name_attribute]);
             }
+            //the first description is the one of torso up
+            if let Ok(descriptions) = read_lines(&language_file(language, "skill_descriptions.txt"))
+            {
+                for (skill, description) in skills.iter_mut().zip(descriptions.into_iter().skip(1))
+                {
+                    skill.description = Some(description);
+                }
+            }
             (attribute_translation_map, skills)
         };
         let helper_function = |parts: &[Armor], part_name| {
-            parts
-                .iter()
-                .cloned()
-                .zip(
-                    BufReader::new(
-                        File::open(format!("./Languages/{language}/{part_name}.txt")).unwrap(),
-                    )
-                    .lines(),
-                )
-                .map(|(mut x, translation)| {
-                    x.translate_skills(&attribute_translation_map);
-                    x.name = translation.unwrap_or_default();
-                    x
-                })
-                .collect_vec()
+            let names = read_translation(
+                &language_file(language, &format!("{part_name}.txt")),
+                parts.len(),
+            )?;
+            Ok::<_, LoadError>(
+                parts
+                    .iter()
+                    .cloned()
+                    .zip(names)
+                    .map(|(mut x, translation)| {
+                        x.translate_skills(&attribute_translation_map);
+                        x.name = translation;
+                        x
+                    })
+                    .collect_vec(),
+            )
         };
-        let head = helper_function(&flags.head, "head");
-        let arms = helper_function(&flags.arms, "arms");
-        let waist = helper_function(&flags.waist, "waist");
-        let legs = helper_function(&flags.legs, "legs");
-        let body = helper_function(&flags.body, "body");
+        let head = helper_function(&flags.head, "head")?;
+        let arms = helper_function(&flags.arms, "arms")?;
+        let waist = helper_function(&flags.waist, "waist")?;
+        let legs = helper_function(&flags.legs, "legs")?;
+        let body = helper_function(&flags.body, "body")?;
         let charms = flags
             .charms
             .clone()
@@ -175,28 +256,39 @@ impl Data {
                 x
             })
             .collect_vec();
-        let relic_head = flags.relic_head.clone();
-        let relic_arms = flags.relic_arms.clone();
-        let relic_waist = flags.relic_waist.clone();
-        let relic_legs = flags.relic_legs.clone();
-        let relic_body = flags.relic_body.clone();
+        let relic_function = |parts: &[Armor]| {
+            parts
+                .iter()
+                .cloned()
+                .map(|mut x| {
+                    x.translate_skills(&attribute_translation_map);
+                    x
+                })
+                .collect_vec()
+        };
+        let relic_head = relic_function(&flags.relic_head);
+        let relic_arms = relic_function(&flags.relic_arms);
+        let relic_waist = relic_function(&flags.relic_waist);
+        let relic_legs = relic_function(&flags.relic_legs);
+        let relic_body = relic_function(&flags.relic_body);
         let decorations = flags
             .decorations
             .clone()
             .into_iter()
-            .zip(
-                BufReader::new(
-                    File::open(format!("./Languages/{language}/decorations.txt")).unwrap(),
-                )
-                .lines(),
-            )
+            .zip(read_translation(
+                &language_file(language, "decorations.txt"),
+                flags.decorations.len(),
+            )?)
             .map(|(mut x, translation)| {
                 x.translate_skills(&attribute_translation_map);
-                x.name = translation.unwrap_or_default();
+                x.name = translation;
                 x
             })
             .collect_vec();
-        Data {
+        let skill_ids = flags.skill_ids.translate(&attribute_translation_map);
+        Ok(Data {
+            possible_relics: possible_relics(&skills, &skill_ids, flags.game.max_slots),
+            skill_ids,
             skills,
             head,
             body,
@@ -210,9 +302,103 @@ impl Data {
             relic_head,
             decorations,
             charms,
+        })
+    }
+}
+
+impl From<&Flags> for Data {
+    /// Untranslated data, names are the japanese ones of the data files
+    fn from(flags: &Flags) -> Self {
+        Data {
+            body: flags.body.clone(),
+            head: flags.head.clone(),
+            arms: flags.arms.clone(),
+            waist: flags.waist.clone(),
+            legs: flags.legs.clone(),
+            relic_body: flags.relic_body.clone(),
+            relic_head: flags.relic_head.clone(),
+            relic_arms: flags.relic_arms.clone(),
+            relic_waist: flags.relic_waist.clone(),
+            relic_legs: flags.relic_legs.clone(),
+            possible_relics: possible_relics(&flags.skills, &flags.skill_ids, flags.game.max_slots),
+            skill_ids: flags.skill_ids.clone(),
+            charms: flags.charms.clone(),
+            decorations: flags.decorations.clone(),
+            skills: flags.skills.clone(),
+        }
+    }
+}
+
+/// Maps the names of one translation of the data to another one
+struct Translation {
+    attributes: HashMap<String, String>,
+    parts: HashMap<String, String>,
+    charms: HashMap<String, String>,
+    skills: Vec<Skill>,
+}
+
+impl Translation {
+    fn new(from: &Data, to: &Data) -> Self {
+        let attributes = from
+            .skills
+            .iter()
+            .zip(&to.skills)
+            .map(|(from, to)| (from.name_attribute.clone(), to.name_attribute.clone()))
+            .unique_by(|(from, _)| from.clone())
+            .collect();
+        let mut parts = HashMap::new();
+        for (from, to) in [
+            (&from.head, &to.head),
+            (&from.body, &to.body),
+            (&from.arms, &to.arms),
+            (&from.waist, &to.waist),
+            (&from.legs, &to.legs),
+        ] {
+            for (from, to) in from.iter().zip(to) {
+                parts.entry(from.name.clone()).or_insert(to.name.clone());
+            }
+        }
+        for (from, to) in from.possible_relics.iter().zip(&to.possible_relics) {
+            parts.entry(from.name.clone()).or_insert(to.name.clone());
+        }
+        for (from, to) in from.decorations.iter().zip(&to.decorations) {
+            parts.entry(from.name.clone()).or_insert(to.name.clone());
+        }
+        let charms = from
+            .charms
+            .iter()
+            .zip(&to.charms)
+            .map(|(from, to)| (from.to_string(), to.to_string()))
+            .collect();
+        Translation {
+            attributes,
+            parts,
+            charms,
+            skills: to.skills.clone(),
         }
     }
+    fn get(map: &HashMap<String, String>, name: &str) -> String {
+        map.get(name).cloned().unwrap_or_else(|| name.to_owned())
+    }
+    fn attribute(&self, name: &str) -> String {
+        Self::get(&self.attributes, name)
+    }
+    fn part(&self, name: &str) -> String {
+        Self::get(&self.parts, name)
+    }
+    fn charm(&self, name: &str) -> String {
+        Self::get(&self.charms, name)
+    }
+    fn skill_group(&self, attribute: &str) -> Vec<Skill> {
+        let attribute = self.attribute(attribute);
+        self.skills
+            .iter()
+            .filter(|skill| skill.name_attribute == attribute)
+            .cloned()
+            .collect()
+    }
 }
+
 #[derive(Clone)]
 pub struct Flags {
     pub components: Vec<Component>,
@@ -229,10 +415,151 @@ pub struct Flags {
     pub charms: Vec<Charm>,
     pub decorations: Vec<Decoration>,
     pub skills: Vec<Skill>,
+    pub skill_ids: SkillIds,
+    pub game: Game,
+}
+
+impl Flags {
+    /// Fails if one of the data files is missing.
+    /// Rows that can't be read and unreadable user files are returned with the data.
+    pub fn load() -> Result<(Flags, Vec<LoadError>), LoadError> {
+        let mut errors = Vec::new();
+        let game = Game::load()?;
+        let body = read_required(&data_file("body.txt"), 1, &mut errors)?;
+        let head = read_required(&data_file("head.txt"), 1, &mut errors)?;
+        let arms = read_required(&data_file("arms.txt"), 1, &mut errors)?;
+        let waist = read_required(&data_file("waist.txt"), 1, &mut errors)?;
+        let legs = read_required(&data_file("legs.txt"), 1, &mut errors)?;
+        let decorations = read_required(&data_file("decorations.txt"), 1, &mut errors)?;
+        let components = read_required(&data_file("components.txt"), 0, &mut errors)?;
+        //torso up is the only skill without an attribute
+        let (skills, skill_errors) = read_csv(&data_file("skills.txt"), 1, |record| {
+            record.get(1).is_some_and(str::is_empty)
+        })?;
+        errors.extend(skill_errors);
+        let mut flags = Flags {
+            relic_body: read_optional(&user_file("relic_body.txt"), 0, &mut errors),
+            relic_head: read_optional(&user_file("relic_head.txt"), 0, &mut errors),
+            relic_arms: read_optional(&user_file("relic_arms.txt"), 0, &mut errors),
+            relic_waist: read_optional(&user_file("relic_waist.txt"), 0, &mut errors),
+            relic_legs: read_optional(&user_file("relic_legs.txt"), 0, &mut errors),
+            charms: read_optional(&user_file("mycharms.txt"), 1, &mut errors),
+            body,
+            head,
+            arms,
+            waist,
+            legs,
+            decorations,
+            skill_ids: SkillIds::new(&skills),
+            skills,
+            components,
+            game,
+        };
+        flags.intern_skills();
+        Ok((flags, errors))
+    }
+    /// Looks up the skill ids of every part, has to be repeated when skill names are changed
+    pub fn intern_skills(&mut self) {
+        for parts in [
+            &mut self.body,
+            &mut self.head,
+            &mut self.arms,
+            &mut self.waist,
+            &mut self.legs,
+            &mut self.relic_body,
+            &mut self.relic_head,
+            &mut self.relic_arms,
+            &mut self.relic_waist,
+            &mut self.relic_legs,
+        ] {
+            intern_skills(parts, &self.skill_ids);
+        }
+        intern_skills(&mut self.decorations, &self.skill_ids);
+        intern_skills(&mut self.charms, &self.skill_ids);
+    }
 }
 
-#[derive(Serialize, Deserialize)]
+const SETTINGS_FILE: &str = "settings.json";
+const LEGACY_SETTINGS_FILE: &str = "settings";
+/// Version of the settings file layout, increase it when a migration is added.
+/// Since version 2 names are stored in japanese, older files use the names of their language.
+const SETTINGS_VERSION: u64 = 3;
+/// `MIGRATIONS[n]` turns a settings file of version `n + 1` into version `n + 2`
+const MIGRATIONS: &[fn(&mut serde_json::Value)] = &[|_| (), hunter_rank_number];
+
+/// Version 3 stores the hunter rank as a number because the ranks depend on the game
+fn hunter_rank_number(value: &mut serde_json::Value) {
+    if let Some(settings) = value.as_object_mut() {
+        let rank = settings
+            .remove("gathering_hall_rank")
+            .and_then(|rank| serde_json::from_value::<GatheringHallRank>(rank).ok())
+            .unwrap_or(GatheringHallRank::All);
+        settings.insert("hunter_rank".to_owned(), (rank as i32).into());
+    }
+}
+
+/// Why the settings were reset to their defaults
+enum SettingsError {
+    /// The file exists but couldn't be opened
+    Open(io::Error),
+    /// The file couldn't be parsed, it was kept as `kept_as` instead of being overwritten on exit
+    Broken { error: String, kept_as: String },
+}
+
+impl SettingsError {
+    fn broken(file: &str, error: impl ToString) -> Self {
+        let kept_as = format!("{file}.broken");
+        let _ = std::fs::rename(user_file(file), user_file(&kept_as));
+        SettingsError::Broken {
+            error: error.to_string(),
+            kept_as,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+#[serde(default)]
 pub struct Settings {
+    /// 1 based index into the hunter ranks of the game, higher ranks include every piece
+    hunter_rank: i32,
+    language: Language,
+    village_rank: i32,
+    weapon_type: WeaponType,
+    weapon_slots: usize,
+    weapon_skill: Option<Requirement>,
+    sex: Sex,
+    skill_type_index: usize,
+    skill_filter: String,
+    skills_needed: Vec<(bool, usize, Vec<Skill>)>,
+    parts_excluded: Vec<String>,
+    /// Pieces every result has to contain, `None` is the charm slot
+    parts_pinned: Vec<(Option<ArmorType>, String)>,
+    trim_count: usize,
+    min_rarity: i32,
+    /// Search with every relic piece that can drop instead of only the owned ones
+    possible_relics: bool,
+    torso_up: bool,
+    /// Most torso up pieces a result may have
+    max_torso_up: usize,
+    presets: Vec<Preset>,
+    preset_name: String,
+}
+
+/// Everything describing what to search for, saved under a name
+#[derive(Serialize, Deserialize, Clone)]
+struct Preset {
+    name: String,
+    weapon_type: WeaponType,
+    weapon_slots: usize,
+    weapon_skill: Option<Requirement>,
+    skills_needed: Vec<(bool, usize, Vec<Skill>)>,
+    parts_excluded: Vec<String>,
+    parts_pinned: Vec<(Option<ArmorType>, String)>,
+}
+
+/// Layout of the bincode settings file written before settings were stored as json
+#[derive(Deserialize)]
+struct LegacySettings {
     gathering_hall_rank: GatheringHallRank,
     language: Language,
     village_rank: i32,
@@ -248,22 +575,161 @@ pub struct Settings {
     min_rarity: i32,
 }
 
+impl From<LegacySettings> for Settings {
+    fn from(legacy: LegacySettings) -> Self {
+        Settings {
+            hunter_rank: legacy.gathering_hall_rank as i32,
+            language: legacy.language,
+            village_rank: legacy.village_rank,
+            weapon_type: legacy.weapon_type,
+            weapon_slots: legacy.weapon_slots,
+            weapon_skill: legacy.weapon_skill,
+            sex: legacy.sex,
+            skill_type_index: legacy.skill_type_index,
+            skill_filter: legacy.skill_filter,
+            skills_needed: legacy.skills_needed,
+            parts_excluded: legacy.parts_excluded,
+            trim_count: legacy.trim_count,
+            min_rarity: legacy.min_rarity,
+            ..Default::default()
+        }
+    }
+}
+
+impl Preset {
+    fn translate(&mut self, translation: &Translation) {
+        if let Some(ref mut weapon_skill) = self.weapon_skill {
+            weapon_skill.name = translation.attribute(&weapon_skill.name);
+        }
+        for (_, _, skills) in self.skills_needed.iter_mut() {
+            if let Some(skill) = skills.first() {
+                *skills = translation.skill_group(&skill.name_attribute);
+            }
+        }
+        self.skills_needed
+            .retain(|(_, _, skills)| !skills.is_empty());
+        for part in self.parts_excluded.iter_mut() {
+            *part = translation.part(part);
+        }
+        for (armor_type, part) in self.parts_pinned.iter_mut() {
+            *part = match armor_type {
+                Some(_) => translation.part(part),
+                None => translation.charm(part),
+            };
+        }
+    }
+}
+
+impl Settings {
+    /// Returns the settings and the version of the file they were read from,
+    /// 0 for the bincode file.
+    /// If a settings file can't be read the defaults are used and the error is returned as well
+    fn load() -> (Settings, u64, Option<SettingsError>) {
+        match File::open(user_file(SETTINGS_FILE)) {
+            Ok(file) => match serde_json::from_reader(file).and_then(Self::migrate) {
+                Ok((settings, version)) => (settings, version, None),
+                Err(error) => {
+                    let error = SettingsError::broken(SETTINGS_FILE, error);
+                    (Default::default(), SETTINGS_VERSION, Some(error))
+                }
+            },
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Self::load_legacy(),
+            Err(error) => (
+                Default::default(),
+                SETTINGS_VERSION,
+                Some(SettingsError::Open(error)),
+            ),
+        }
+    }
+    /// Reads the bincode file written before settings were stored as json
+    fn load_legacy() -> (Settings, u64, Option<SettingsError>) {
+        match File::open(user_file(LEGACY_SETTINGS_FILE)) {
+            Ok(file) => match bincode::deserialize_from::<_, LegacySettings>(file) {
+                Ok(legacy) => (legacy.into(), 0, None),
+                Err(error) => {
+                    let error = SettingsError::broken(LEGACY_SETTINGS_FILE, error);
+                    (Default::default(), SETTINGS_VERSION, Some(error))
+                }
+            },
+            Err(error) if error.kind() == io::ErrorKind::NotFound => {
+                (Default::default(), SETTINGS_VERSION, None)
+            }
+            Err(error) => (
+                Default::default(),
+                SETTINGS_VERSION,
+                Some(SettingsError::Open(error)),
+            ),
+        }
+    }
+    fn migrate(mut value: serde_json::Value) -> serde_json::Result<(Settings, u64)> {
+        let version = value
+            .get("version")
+            .and_then(serde_json::Value::as_u64)
+            .unwrap_or(1);
+        for migration in MIGRATIONS.iter().skip(version.saturating_sub(1) as usize) {
+            migration(&mut value);
+        }
+        Ok((serde_json::from_value(value)?, version))
+    }
+    fn translate(&mut self, translation: &Translation) {
+        let name = take(&mut self.preset_name);
+        let mut current = self.to_preset(name);
+        current.translate(translation);
+        self.apply_preset(current);
+        for preset in self.presets.iter_mut() {
+            preset.translate(translation);
+        }
+    }
+    fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
+        let mut value = serde_json::to_value(self)?;
+        value["version"] = SETTINGS_VERSION.into();
+        serde_json::to_writer_pretty(File::create(user_file(SETTINGS_FILE))?, &value)?;
+        Ok(())
+    }
+    fn to_preset(&self, name: String) -> Preset {
+        Preset {
+            name,
+            weapon_type: self.weapon_type,
+            weapon_slots: self.weapon_slots,
+            weapon_skill: self.weapon_skill.clone(),
+            skills_needed: self.skills_needed.clone(),
+            parts_excluded: self.parts_excluded.clone(),
+            parts_pinned: self.parts_pinned.clone(),
+        }
+    }
+    fn apply_preset(&mut self, preset: Preset) {
+        self.preset_name = preset.name;
+        self.weapon_type = preset.weapon_type;
+        self.weapon_slots = preset.weapon_slots;
+        self.weapon_skill = preset.weapon_skill;
+        self.skills_needed = preset.skills_needed;
+        self.parts_excluded = preset.parts_excluded;
+        self.parts_pinned = preset.parts_pinned;
+    }
+}
+
 impl Default for Settings {
     fn default() -> Self {
         Settings {
             language: Language::German,
-            gathering_hall_rank: GatheringHallRank::All,
+            hunter_rank: i32::MAX,
             village_rank: 10,
             weapon_type: WeaponType::Melee,
             weapon_slots: 0,
             sex: Sex::Male,
             skill_type_index: 0,
             min_rarity: 1,
+            possible_relics: false,
+            torso_up: true,
+            max_torso_up: 4,
             skill_filter: String::default(),
             skills_needed: Vec::default(),
             parts_excluded: Vec::default(),
+            parts_pinned: Vec::default(),
             trim_count: 20,
             weapon_skill: Default::default(),
+            presets: Vec::default(),
+            preset_name: String::default(),
         }
     }
 }
@@ -278,10 +744,27 @@ impl<T> Default for SearchStatus<T> {
     }
 }
 
+#[derive(Clone, Debug)]
+struct SkillTotal {
+    attribute: String,
+    points: i32,
+    activated: Option<Skill>,
+}
+
 #[derive(Clone, Debug)]
 pub enum FindResults {
     ArmorSets(Vec<FindResult>),
     Skills(Vec<usize>),
+    IdealCharm(Option<Charm>),
+    /// The ideal charm search was cancelled, its result belongs to no search anymore
+    IdealCharmCancelled,
+}
+
+/// Percentage sent by long running searches through the progress worker
+#[derive(Clone, Debug)]
+pub enum Progress {
+    AdditionalSkills(f32),
+    IdealCharm(f32),
 }
 
 struct CharmsState {
@@ -290,62 +773,135 @@ struct CharmsState {
     pub slots: i32,
 }
 
-enum RelicArmorSkill {
-    Slots(i32),
-    Skill(Requirement),
-}
-impl Default for RelicArmorSkill {
-    fn default() -> Self {
-        RelicArmorSkill::Slots(0)
-    }
-}
-impl RelicArmorSkill {
-    pub fn get_skill_name(&self) -> &str {
-        match &self {
-            RelicArmorSkill::Slots(_) => "",
-            RelicArmorSkill::Skill(requirement) => &requirement.name,
-        }
-    }
-    pub fn is_empty(&self) -> bool {
-        match &self {
-            RelicArmorSkill::Slots(x) => *x == 0,
-            RelicArmorSkill::Skill(requirement) => requirement.points == 0,
-        }
-    }
-    pub fn slots(&self) -> i32 {
-        match self {
-            RelicArmorSkill::Slots(x) => *x,
-            RelicArmorSkill::Skill(_requirement) => 0,
-        }
-    }
-    pub fn points(&self) -> i32 {
-        match self {
-            RelicArmorSkill::Slots(_) => 0,
-            RelicArmorSkill::Skill(requirement) => requirement.points,
-        }
-    }
-}
 #[derive(Default)]
 struct ArmorState {
     pub pieces: Vec<(ArmorType, Armor)>,
-    pub skill: RelicArmorSkill,
+    pub skill: Option<Requirement>,
+    pub slots: i32,
+    pub defence_min: i32,
+    pub defence_max: i32,
+    /// Fire, water, thunder, ice and dragon resistance
+    pub resistances: [i32; 5],
     pub weapon_type: WeaponType,
     pub armor_type: ArmorType,
     pub name: String,
+    /// Index of the piece loaded into the editor, adding replaces it
+    pub editing: Option<usize>,
 }
 
 impl ArmorState {
-    pub fn piece_to_string(piece: &(ArmorType, Armor)) -> String {
+    pub fn piece_to_string(piece: &(ArmorType, Armor), strings: &Strings) -> String {
         let (_t, p) = piece;
         let mut result = p.name.clone();
-        if let Some(points) = p.ability_1_points {
-            result.push_str(&format!(" {} {points}", p.ability_1_name));
+        for skill in &p.skills {
+            result.push_str(&format!(" {} {}", skill.name, skill.points));
         }
         for _ in 0..(p.slots) {
             result.push_str(" ○");
         }
+        if p.defence_max > 0 {
+            result.push_str(", ");
+            result.push_str(&strings.format(
+                "%1-%2 Def, %3 Fir, %4 Wat, %5 Ice, %6 Thn, %7 Drg",
+                &[
+                    &p.defence_min,
+                    &p.defence_max,
+                    &p.defence_fire,
+                    &p.defence_water,
+                    &p.defence_ice,
+                    &p.defence_thunder,
+                    &p.defence_dragon,
+                ],
+            ));
+        }
         result
     }
+    pub fn is_empty(&self) -> bool {
+        self.slots == 0 && self.skill.as_ref().is_none_or(|skill| skill.points == 0)
+    }
+    /// The piece described by the editor.
+    /// Unnamed pieces are named after their skill, shortened so that the name stays readable.
+    pub fn piece(&self) -> Armor {
+        let skill = self.skill.clone().filter(|skill| skill.points != 0);
+        let name = if self.name.is_empty() {
+            skill.as_ref().map_or_else(
+                || "○".repeat(self.slots as usize),
+                |skill| skill.name.chars().take(4).collect(),
+            )
+        } else {
+            self.name.clone()
+        };
+        let [fire, water, thunder, ice, dragon] = self.resistances;
+        Armor {
+            name: format!("{RELIC_PREFIX}{name}"),
+            weapon_type: self.weapon_type as i32,
+            slots: self.slots,
+            rarity: 10,
+            defence_min: self.defence_min,
+            defence_max: self.defence_max,
+            defence_fire: fire,
+            defence_water: water,
+            defence_thunder: thunder,
+            defence_ice: ice,
+            defence_dragon: dragon,
+            skills: skill
+                .map(|skill| SkillPoints::new(skill.name, skill.points))
+                .into_iter()
+                .collect(),
+            ..Default::default()
+        }
+    }
+    /// Loads a piece into the editor
+    pub fn edit(&mut self, index: usize) {
+        let Some((armor_type, piece)) = self.pieces.get(index) else {
+            return;
+        };
+        self.armor_type = *armor_type;
+        self.name = piece
+            .name
+            .strip_prefix(RELIC_PREFIX)
+            .unwrap_or(&piece.name)
+            .to_owned();
+        self.weapon_type = match piece.weapon_type {
+            1 => WeaponType::Melee,
+            2 => WeaponType::Marksman,
+            _ => WeaponType::Both,
+        };
+        self.skill = piece.skills.first().map(|skill| Requirement {
+            name: skill.name.clone(),
+            points: skill.points,
+            ..Default::default()
+        });
+        self.slots = piece.slots;
+        self.defence_min = piece.defence_min;
+        self.defence_max = piece.defence_max;
+        self.resistances = [
+            piece.defence_fire,
+            piece.defence_water,
+            piece.defence_thunder,
+            piece.defence_ice,
+            piece.defence_dragon,
+        ];
+        self.editing = Some(index);
+    }
+    /// Empties the editor, the piece list and the armor type are kept
+    pub fn clear(&mut self) {
+        *self = ArmorState {
+            pieces: take(&mut self.pieces),
+            armor_type: self.armor_type,
+            ..Default::default()
+        };
+    }
+}
+
+#[derive(Default)]
+struct ImportState {
+    pub path: String,
+    pub charms: Vec<Charm>,
+    pub rejected: Vec<String>,
+    pub status: String,
+    /// The export file exists already, it is only overwritten after confirming
+    pub confirm_overwrite: bool,
 }
 
 #[derive(Default)]
@@ -354,6 +910,7 @@ enum UIState {
     Default,
     Charms(CharmsState),
     Armor(ArmorState),
+    Import(ImportState),
 }
 pub struct State {
     pub weapon_skills: combo_box::State<String>,
@@ -361,45 +918,270 @@ pub struct State {
     ui_state: UIState,
     flags: Flags,
     settings: Settings,
-    search_status: SearchStatus<FindResult>,
+    search_status: SearchStatus<(FindResult, Vec<SkillTotal>)>,
     search_status_skills: SearchStatus<usize>,
+    /// `None` until the ideal charm is searched for the current results
+    ideal_charm: Option<SearchStatus<Charm>>,
+    /// Set to stop the running ideal charm search
+    ideal_charm_cancelled: Arc<AtomicBool>,
     skill_types: Vec<String>,
-    progress_sender: Option<mpsc::Sender<Option<f32>>>,
+    progress_sender: Option<mpsc::Sender<Progress>>,
     skills_grouped: HashMap<String, Vec<Skill>>,
+    strings: Strings,
+    load_errors: Vec<LoadError>,
+    /// Messages about the settings and pins, shown until they are dismissed
+    notices: Vec<String>,
+    /// Closing the window once more quits without the settings
+    settings_save_failed: bool,
+    /// Shared by all searches of the session
+    decoration_cache: Arc<DecorationCache>,
+    /// Toggled with F12
+    debug_panel: bool,
 }
 impl State {
-    pub fn new(flags: Flags) -> Self {
-        let settings: Settings = if let Ok(file) = File::open("./settings") {
-            bincode::deserialize_from(file).unwrap_or_default()
-        } else {
-            Default::default()
+    /// Falls back to english if the files of the saved language can't be read
+    pub fn new(mut flags: Flags, mut load_errors: Vec<LoadError>) -> Result<Self, LoadError> {
+        //charms and relics saved by older versions use translated skill names
+        let aliases = skill_aliases(&flags.skills, &flags.skills);
+        let base_name = |name: &mut String| {
+            if let Some(base) = aliases.get(&name.to_lowercase()) {
+                name.clone_from(base);
+            }
         };
-        let data = Data::translate(settings.language, &flags);
+        for charm in flags.charms.iter_mut() {
+            base_name(&mut charm.skill_1);
+            base_name(&mut charm.skill_2);
+        }
+        for relic in flags
+            .relic_head
+            .iter_mut()
+            .chain(flags.relic_body.iter_mut())
+            .chain(flags.relic_arms.iter_mut())
+            .chain(flags.relic_waist.iter_mut())
+            .chain(flags.relic_legs.iter_mut())
+        {
+            for skill in relic.skills.iter_mut() {
+                base_name(&mut skill.name);
+            }
+        }
+        flags.intern_skills();
+        let (mut settings, version, settings_error) = Settings::load();
+        let data = match Data::translate(settings.language, &flags) {
+            Ok(data) => data,
+            Err(error) => {
+                load_errors.push(error);
+                settings.language = Language::English;
+                Data::translate(settings.language, &flags)?
+            }
+        };
+        if version >= 2 {
+            settings.translate(&Translation::new(&Data::from(&flags), &data));
+        }
         let (skills_grouped, skill_types) = Skill::group(&data.skills);
-        let weapon_skills = combo_box::State::new(
-            skills_grouped
-                .iter()
-                .filter_map(|(k, v)| {
-                    v[0].max_weapon_skill_points
-                        .is_some_and(|x| x > 0)
-                        .then_some(k.clone())
-                })
-                .collect(),
-        );
-        State {
+        let weapon_skills = Self::weapon_skills(&skills_grouped);
+        let strings = Strings::load(settings.language);
+        let notices = settings_error
+            .map(|error| match error {
+                SettingsError::Open(error) => strings.format(
+                    "The settings could not be opened (%1), the defaults are used",
+                    &[&error],
+                ),
+                SettingsError::Broken { error, kept_as } => strings.format(
+                    "The settings could not be read (%1). They were reset, the old file was kept as %2",
+                    &[&error, &kept_as],
+                ),
+            })
+            .into_iter()
+            .collect();
+        Ok(State {
             weapon_skills,
             data,
             flags,
             skills_grouped,
             skill_types,
+            strings,
             settings,
             progress_sender: None,
             search_status: Default::default(),
             search_status_skills: Default::default(),
+            ideal_charm: None,
+            ideal_charm_cancelled: Default::default(),
             ui_state: Default::default(),
+            load_errors,
+            notices,
+            settings_save_failed: false,
+            decoration_cache: Default::default(),
+            debug_panel: false,
+        })
+    }
+
+    fn cancel_ideal_charm(&mut self) {
+        self.ideal_charm_cancelled.store(true, Ordering::Relaxed);
+        self.ideal_charm = None;
+    }
+
+    fn weapon_skills(skills_grouped: &HashMap<String, Vec<Skill>>) -> combo_box::State<String> {
+        combo_box::State::new(
+            skills_grouped
+                .iter()
+                .filter_map(|(k, v)| {
+                    v[0].max_weapon_skill_points
+                        .is_some_and(|x| x > 0)
+                        .then_some(k.clone())
+                })
+                .collect(),
+        )
+    }
+
+    /// Maps the skill attributes of the current language to the japanese ones
+    fn base_attributes(&self) -> HashMap<String, String> {
+        self.data
+            .skills
+            .iter()
+            .zip(&self.flags.skills)
+            .map(|(translated, base)| {
+                (
+                    translated.name_attribute.clone(),
+                    base.name_attribute.clone(),
+                )
+            })
+            .collect()
+    }
+
+    fn set_charms(&mut self, mut charms: Vec<Charm>) {
+        intern_skills(&mut charms, &self.data.skill_ids);
+        let base_attributes = self.base_attributes();
+        self.flags.charms = charms
+            .iter()
+            .cloned()
+            .map(|mut charm| {
+                charm.translate_skills(&base_attributes);
+                charm
+            })
+            .collect();
+        if let Err(error) = save_charms(&self.flags.charms) {
+            self.notices.push(
+                self.strings
+                    .format("Your charms could not be saved (%1)", &[&error]),
+            );
+        }
+        self.data.charms = charms;
+    }
+
+    /// Attributes that are on the same armor pieces as the required skills
+    fn related_attributes(&self) -> HashSet<&str> {
+        let required = self
+            .settings
+            .skills_needed
+            .iter()
+            .filter_map(|(_, _, skills)| skills.first())
+            .map(|skill| &skill.name_attribute)
+            .collect_vec();
+        [
+            &self.data.head,
+            &self.data.body,
+            &self.data.arms,
+            &self.data.waist,
+            &self.data.legs,
+        ]
+        .into_iter()
+        .flatten()
+        .filter(|armor| {
+            required
+                .iter()
+                .any(|name| armor.points(self.data.skill_ids.get(name)) > 0)
+        })
+        .flat_map(|armor| armor.skill_names())
+        .collect()
+    }
+
+    /// Skills saved in the settings don't have descriptions, so they are looked up in the data
+    fn skill_description(&self, skill: &Skill) -> String {
+        self.skills_grouped
+            .get(&skill.name_attribute)
+            .and_then(|group| group.iter().find(|s| s.name == skill.name))
+            .and_then(|s| s.description.clone())
+            .unwrap_or_default()
+    }
+
+    fn skill_totals(&self, result: &FindResult) -> Vec<SkillTotal> {
+        let body = self.data.part(ArmorType::Chest, result.body_index);
+        let mut body_count = 1;
+        let mut pieces: Vec<(&dyn SkillPoint, i32)> = Vec::new();
+        let others = [
+            result
+                .head_index
+                .map(|i| self.data.part(ArmorType::Head, i)),
+            result
+                .arms_index
+                .map(|i| self.data.part(ArmorType::Arms, i)),
+            result
+                .waist_index
+                .map(|i| self.data.part(ArmorType::Waist, i)),
+            result
+                .legs_index
+                .map(|i| self.data.part(ArmorType::Legs, i)),
+        ];
+        for piece in &others {
+            match piece {
+                Some(piece) => pieces.push((*piece, 1)),
+                //torso up
+                None => body_count += 1,
+            }
         }
+        pieces.push((body, body_count));
+        pieces.push((&self.data.charms[result.charms_index], 1));
+        for &(count, index) in &result.decorations_count_indices {
+            pieces.push((&self.data.decorations[index], count as i32));
+        }
+        let weapon_type = self.settings.weapon_type as i32;
+        self.data
+            .skills
+            .iter()
+            .map(|skill| &skill.name_attribute)
+            .unique()
+            .filter_map(|attribute| {
+                let id = self.data.skill_ids.get(attribute);
+                let mut points = pieces
+                    .iter()
+                    .map(|(piece, count)| piece.points(id) * count)
+                    .sum::<i32>();
+                if let Some(weapon_skill) = self
+                    .settings
+                    .weapon_skill
+                    .as_ref()
+                    .filter(|skill| &skill.name == attribute)
+                {
+                    points += weapon_skill.points;
+                }
+                (points != 0).then(|| SkillTotal {
+                    attribute: attribute.clone(),
+                    points,
+                    activated: self
+                        .skills_grouped
+                        .get(attribute)
+                        .and_then(|group| Skill::activated(group, points, weapon_type))
+                        .cloned(),
+                })
+            })
+            .sorted_by_key(|total| total.activated.is_none())
+            .collect()
     }
 }
+fn save_charms(charms: &[Charm]) -> csv::Result<()> {
+    write_charms(&user_file("mycharms.txt"), charms)
+}
+/// Writes charms in the layout of mycharms.txt, the first row is skipped when reading it
+fn write_charms(path: &Path, charms: &[Charm]) -> csv::Result<()> {
+    let mut writer = csv::WriterBuilder::new()
+        .has_headers(false)
+        .from_path(path)?;
+    writer.serialize(Charm::default())?;
+    for charm in charms {
+        writer.serialize(charm)?;
+    }
+    Ok(writer.flush()?)
+}
 fn progress_worker() -> impl Stream<Item = Message> {
     stream::channel(100, |mut output| async move {
         let (sender, mut receiver) = mpsc::channel(100);
@@ -409,9 +1191,8 @@ fn progress_worker() -> impl Stream<Item = Message> {
             .unwrap();
         loop {
             use iced::futures::StreamExt;
-            if let Some(input) = receiver.select_next_some().await {
-                output.send(Message::SetProgress(input)).await.unwrap();
-            }
+            let input = receiver.select_next_some().await;
+            output.send(Message::SetProgress(input)).await.unwrap();
         }
     })
 }
@@ -432,6 +1213,18 @@ pub fn update(appstate: &mut State, message: Message) -> Task<Message> {
             appstate.settings.min_rarity = rarity;
             Task::none()
         }
+        Message::PossibleRelicsToggled(enabled) => {
+            appstate.settings.possible_relics = enabled;
+            Task::none()
+        }
+        Message::TorsoUpToggled(enabled) => {
+            appstate.settings.torso_up = enabled;
+            Task::none()
+        }
+        Message::MaxTorsoUpSelected(count) => {
+            appstate.settings.max_torso_up = count;
+            Task::none()
+        }
         Message::CustomClear => {
             if let UIState::Charms(ref mut state) = appstate.ui_state {
                 state.skills.clear();
@@ -442,63 +1235,105 @@ pub fn update(appstate: &mut State, message: Message) -> Task<Message> {
         Message::SetSlots(slots) => {
             match appstate.ui_state {
                 UIState::Charms(ref mut state) => state.slots = slots,
-                UIState::Armor(ref mut state) => state.skill = RelicArmorSkill::Slots(slots),
+                UIState::Armor(ref mut state) => state.slots = slots,
                 UIState::Default => appstate.settings.weapon_slots = slots as usize,
+                UIState::Import(_) => (),
             }
             Task::none()
         }
         Message::CustomSave => {
             match appstate.ui_state {
                 UIState::Charms(ref mut state) => {
-                    // let mut data_charms = appstate.data.charms.unwrap();
-                    let mut writer = csv::WriterBuilder::new()
-                        .has_headers(false)
-                        .from_path("./mycharms.txt")
-                        .unwrap();
-                    writer.serialize(Charm::default()).unwrap();
-                    for charm in &state.charms {
-                        writer.serialize(charm).unwrap();
+                    let charms = take(&mut state.charms);
+                    appstate.set_charms(charms);
+                    appstate.ui_state = UIState::Default;
+                }
+                UIState::Import(ref mut state) => {
+                    let mut charms = appstate.data.charms.clone();
+                    for mut charm in take(&mut state.charms) {
+                        charm.intern_skills(&appstate.data.skill_ids);
+                        if !charms.iter().any(|known| known.is_equivalent(&charm)) {
+                            charms.push(charm);
+                        }
                     }
-                    appstate.data.charms = take(&mut state.charms);
+                    appstate.set_charms(charms);
                     appstate.ui_state = UIState::Default;
                 }
                 UIState::Armor(ref mut state) => {
-                    let helper = |data_custom: &mut Vec<Armor>, path: &str, t: ArmorType| {
-                        let mut writer = csv::WriterBuilder::new()
-                            .has_headers(false)
-                            .from_path(path)
-                            .unwrap();
+                    let base_attributes = appstate
+                        .data
+                        .skills
+                        .iter()
+                        .zip(&appstate.flags.skills)
+                        .map(|(translated, base)| {
+                            (
+                                translated.name_attribute.clone(),
+                                base.name_attribute.clone(),
+                            )
+                        })
+                        .collect();
+                    let skill_ids = appstate.data.skill_ids.clone();
+                    let helper = |data_custom: &mut Vec<Armor>,
+                                  flags_custom: &mut Vec<Armor>,
+                                  path: PathBuf,
+                                  t: ArmorType| {
                         data_custom.clear();
+                        flags_custom.clear();
                         for (_t, piece) in state.pieces.iter().filter(|(x, _p)| t == *x) {
-                            writer.serialize(piece).unwrap();
-                            data_custom.push(piece.clone());
+                            let mut piece = piece.clone();
+                            piece.intern_skills(&skill_ids);
+                            let mut base_piece = piece.clone();
+                            base_piece.translate_skills(&base_attributes);
+                            flags_custom.push(base_piece);
+                            data_custom.push(piece);
+                        }
+                        let mut writer = csv::WriterBuilder::new()
+                            .has_headers(false)
+                            .from_path(path)?;
+                        for piece in flags_custom.iter() {
+                            writer.serialize(piece)?;
                         }
+                        Ok::<_, csv::Error>(writer.flush()?)
                     };
-                    helper(
-                        &mut appstate.data.relic_head,
-                        "./relic_head.txt",
-                        ArmorType::Head,
-                    );
-                    helper(
-                        &mut appstate.data.relic_arms,
-                        "./relic_arms.txt",
-                        ArmorType::Arms,
-                    );
-                    helper(
-                        &mut appstate.data.relic_body,
-                        "./relic_chest.txt",
-                        ArmorType::Chest,
-                    );
-                    helper(
-                        &mut appstate.data.relic_waist,
-                        "./relic_waist.txt",
-                        ArmorType::Waist,
-                    );
-                    helper(
-                        &mut appstate.data.relic_legs,
-                        "./relic_legs.txt",
-                        ArmorType::Legs,
-                    );
+                    let saved = [
+                        helper(
+                            &mut appstate.data.relic_head,
+                            &mut appstate.flags.relic_head,
+                            user_file("relic_head.txt"),
+                            ArmorType::Head,
+                        ),
+                        helper(
+                            &mut appstate.data.relic_arms,
+                            &mut appstate.flags.relic_arms,
+                            user_file("relic_arms.txt"),
+                            ArmorType::Arms,
+                        ),
+                        helper(
+                            &mut appstate.data.relic_body,
+                            &mut appstate.flags.relic_body,
+                            user_file("relic_body.txt"),
+                            ArmorType::Chest,
+                        ),
+                        helper(
+                            &mut appstate.data.relic_waist,
+                            &mut appstate.flags.relic_waist,
+                            user_file("relic_waist.txt"),
+                            ArmorType::Waist,
+                        ),
+                        helper(
+                            &mut appstate.data.relic_legs,
+                            &mut appstate.flags.relic_legs,
+                            user_file("relic_legs.txt"),
+                            ArmorType::Legs,
+                        ),
+                    ];
+                    if let Some(error) = saved.into_iter().find_map(Result::err) {
+                        appstate.notices.push(
+                            appstate
+                                .strings
+                                .format("Your relics could not be saved (%1)", &[&error]),
+                        );
+                    }
                     appstate.ui_state = UIState::Default;
                 }
                 _ => (),
@@ -512,6 +1347,14 @@ pub fn update(appstate: &mut State, message: Message) -> Task<Message> {
                 }
                 UIState::Armor(ref mut state) => {
                     state.pieces.remove(index);
+                    state.editing = match state.editing {
+                        Some(editing) if editing > index => Some(editing - 1),
+                        Some(editing) if editing == index => None,
+                        editing => editing,
+                    };
+                }
+                UIState::Import(ref mut state) => {
+                    state.charms.remove(index);
                 }
                 _ => (),
             }
@@ -528,22 +1371,17 @@ pub fn update(appstate: &mut State, message: Message) -> Task<Message> {
                             points_1: req_1.points,
                             points_2: req_2.map(|r| r.points),
                             skill_2: req_2.map_or(String::new(), |r| r.name.clone()),
+                            ..Default::default()
                         });
                     }
                 }
                 UIState::Armor(ref mut state) => {
-                    let piece = Armor {
-                        name: format!("🪙 {} {}", state.name, &state.skill.get_skill_name()[..4]),
-                        weapon_type: take(&mut state.weapon_type) as i32,
-                        slots: state.skill.slots(),
-                        rarity: 10,
-                        ability_1_name: state.skill.get_skill_name().to_string(),
-                        ability_1_points: Some(state.skill.points()),
-                        ..Default::default()
-                    };
-                    state.pieces.push((state.armor_type, piece));
-                    state.name = Default::default();
-                    state.skill = Default::default();
+                    let piece = (state.armor_type, state.piece());
+                    match state.editing {
+                        Some(index) => state.pieces[index] = piece,
+                        None => state.pieces.push(piece),
+                    }
+                    state.clear();
                 }
                 _ => (),
             }
@@ -557,12 +1395,8 @@ pub fn update(appstate: &mut State, message: Message) -> Task<Message> {
                     }
                 }
                 UIState::Armor(ref mut state) => {
-                    if let RelicArmorSkill::Skill(Requirement {
-                        name: _,
-                        points: ref mut skillpoints,
-                    }) = state.skill
-                    {
-                        *skillpoints = points;
+                    if let Some(ref mut skill) = state.skill {
+                        skill.points = points;
                     }
                 }
                 _ => (),
@@ -585,30 +1419,89 @@ pub fn update(appstate: &mut State, message: Message) -> Task<Message> {
             appstate.settings.parts_excluded.remove(idx);
             Task::none()
         }
-        Message::PartExcludedAdded(part) => {
-            appstate.settings.parts_excluded.push(part);
+        Message::PartExcludedAdded(part) => {
+            appstate.settings.parts_excluded.push(part);
+            update(appstate, Message::Search)
+        }
+        Message::PartsPinnedClear => {
+            appstate.settings.parts_pinned.clear();
+            update(appstate, Message::Search)
+        }
+        Message::PartPinnedRemoved(idx) => {
+            appstate.settings.parts_pinned.remove(idx);
+            update(appstate, Message::Search)
+        }
+        Message::PartPinned(armor_type, part) => {
+            appstate.settings.parts_excluded.retain(|x| x != &part);
+            appstate
+                .settings
+                .parts_pinned
+                .retain(|(t, _)| *t != armor_type);
+            appstate.settings.parts_pinned.push((armor_type, part));
             update(appstate, Message::Search)
         }
         Message::LanguageChanged(language) => {
-            appstate.data = Data::translate(language, &appstate.flags);
+            let data = match Data::translate(language, &appstate.flags) {
+                Ok(data) => data,
+                Err(error) => {
+                    appstate.load_errors.push(error);
+                    return Task::none();
+                }
+            };
+            appstate
+                .settings
+                .translate(&Translation::new(&appstate.data, &data));
+            appstate.data = data;
+            appstate.strings = Strings::load(language);
             (appstate.skills_grouped, appstate.skill_types) = Skill::group(&appstate.data.skills);
+            appstate.weapon_skills = State::weapon_skills(&appstate.skills_grouped);
             appstate.settings.language = language;
-            update(appstate, Message::ClearRequiredSkills)
+            appstate.cancel_ideal_charm();
+            if let SearchStatus::Found(ref mut sets) = appstate.search_status {
+                let mut sets = take(sets);
+                for (set, skill_totals) in sets.iter_mut() {
+                    *skill_totals = appstate.skill_totals(set);
+                }
+                appstate.search_status = SearchStatus::Found(sets);
+            }
+            Task::none()
         }
         Message::ClearRequiredSkills => {
             match appstate.ui_state {
                 UIState::Default => appstate.settings.skills_needed.clear(),
                 UIState::Charms(ref mut state) => state.skills.clear(),
-                UIState::Armor(ref mut state) => state.skill = RelicArmorSkill::Slots(0),
+                UIState::Armor(ref mut state) => state.skill = None,
+                UIState::Import(_) => (),
             };
             Task::none()
         }
         Message::EventOccured(event) => {
             if Event::Window(window::Event::CloseRequested) == event {
-                bincode::serialize_into(File::create("./settings").unwrap(), &appstate.settings)
-                    .unwrap();
+                let mut settings = appstate.settings.clone();
+                settings.translate(&Translation::new(
+                    &appstate.data,
+                    &Data::from(&appstate.flags),
+                ));
+                if let Err(error) = settings.save() {
+                    eprintln!("The settings could not be saved: {error}");
+                    if !appstate.settings_save_failed {
+                        appstate.settings_save_failed = true;
+                        appstate.notices.push(appstate.strings.format(
+                            "The settings could not be saved (%1). Close the window again to quit anyway",
+                            &[&error],
+                        ));
+                        return Task::none();
+                    }
+                }
                 window::get_oldest().then(|id| window::close(id.unwrap()))
             } else {
+                if let Event::Keyboard(keyboard::Event::KeyPressed {
+                    key: keyboard::Key::Named(Named::F12),
+                    ..
+                }) = event
+                {
+                    appstate.debug_panel = !appstate.debug_panel;
+                }
                 Task::none()
             }
         }
@@ -629,8 +1522,9 @@ pub fn update(appstate: &mut State, message: Message) -> Task<Message> {
                     state.skills.remove(index);
                 }
                 UIState::Armor(ref mut state) => {
-                    state.skill = RelicArmorSkill::Slots(0);
+                    state.skill = None;
                 }
+                UIState::Import(_) => (),
             };
             Task::none()
         }
@@ -645,11 +1539,18 @@ pub fn update(appstate: &mut State, message: Message) -> Task<Message> {
                     appstate.settings.skills_needed.push((true, 0, skill));
                 }
                 UIState::Charms(ref mut state) => {
-                    state.skills.push(Requirement { name, points: 0 });
+                    state.skills.push(Requirement {
+                        name,
+                        ..Default::default()
+                    });
                 }
                 UIState::Armor(ref mut state) => {
-                    state.skill = RelicArmorSkill::Skill(Requirement { name, points: 0 });
+                    state.skill = Some(Requirement {
+                        name,
+                        ..Default::default()
+                    });
                 }
+                UIState::Import(_) => (),
             }
             Task::none()
         }
@@ -692,7 +1593,10 @@ pub fn update(appstate: &mut State, message: Message) -> Task<Message> {
             Task::none()
         }
         Message::WeaponSkillSelected(name) => {
-            appstate.settings.weapon_skill = Some(Requirement { name, points: 0 });
+            appstate.settings.weapon_skill = Some(Requirement {
+                name,
+                ..Default::default()
+            });
             Task::none()
         }
         Message::WeaponPointsSelected(points) => {
@@ -705,34 +1609,62 @@ pub fn update(appstate: &mut State, message: Message) -> Task<Message> {
             appstate.settings.village_rank = rank;
             Task::none()
         }
-        Message::GatheringHallRankSelected(rank) => {
-            appstate.settings.gathering_hall_rank = rank;
+        Message::HunterRankSelected(rank) => {
+            appstate.settings.hunter_rank = rank.rank;
             Task::none()
         }
         Message::SetsFound(results) => {
             match results {
-                FindResults::ArmorSets(sets) => appstate.search_status = SearchStatus::Found(sets),
+                FindResults::ArmorSets(sets) => {
+                    let sets = sets
+                        .into_iter()
+                        .map(|set| {
+                            let skill_totals = appstate.skill_totals(&set);
+                            (set, skill_totals)
+                        })
+                        .collect();
+                    appstate.search_status = SearchStatus::Found(sets);
+                    appstate.ideal_charm = None;
+                }
                 FindResults::Skills(skills) => {
                     appstate.search_status_skills = SearchStatus::Found(skills)
                 }
+                FindResults::IdealCharm(charm) => {
+                    appstate.ideal_charm = Some(SearchStatus::Found(charm.into_iter().collect()));
+                }
+                FindResults::IdealCharmCancelled => (),
             }
             Task::none()
         }
-        msg @ (Message::Search | Message::SearchAdditionalSkills) => {
+        Message::IdealCharmCancelled => {
+            appstate.cancel_ideal_charm();
+            Task::none()
+        }
+        msg @ (Message::Search | Message::SearchAdditionalSkills | Message::SearchIdealCharm) => {
             match msg {
-                Message::Search => appstate.search_status = SearchStatus::Searching(0.0),
+                Message::Search => {
+                    appstate.cancel_ideal_charm();
+                    appstate.search_status = SearchStatus::Searching(0.0);
+                }
+                Message::SearchIdealCharm => {
+                    appstate.ideal_charm_cancelled = Default::default();
+                    appstate.ideal_charm = Some(SearchStatus::Searching(0.0));
+                }
                 _ => appstate.search_status_skills = SearchStatus::Searching(0.0),
             }
-            let mut head = appstate.data.head.clone();
-            head.extend_from_slice(&appstate.data.relic_head);
-            let mut body = appstate.data.body.clone();
-            body.extend_from_slice(&appstate.data.relic_body);
-            let mut waist = appstate.data.waist.clone();
-            waist.extend_from_slice(&appstate.data.relic_waist);
-            let mut legs = appstate.data.legs.clone();
-            legs.extend_from_slice(&appstate.data.relic_legs);
-            let mut arms = appstate.data.arms.clone();
-            arms.extend_from_slice(&appstate.data.relic_arms);
+            let ideal_charm_cancelled = appstate.ideal_charm_cancelled.clone();
+            let possible_relics: &[Armor] = if appstate.settings.possible_relics {
+                &appstate.data.possible_relics
+            } else {
+                &[]
+            };
+            let with_relics =
+                |parts: &[Armor], relics: &[Armor]| [parts, relics, possible_relics].concat();
+            let head = with_relics(&appstate.data.head, &appstate.data.relic_head);
+            let body = with_relics(&appstate.data.body, &appstate.data.relic_body);
+            let waist = with_relics(&appstate.data.waist, &appstate.data.relic_waist);
+            let legs = with_relics(&appstate.data.legs, &appstate.data.relic_legs);
+            let arms = with_relics(&appstate.data.arms, &appstate.data.relic_arms);
             let charms = appstate.data.charms.clone();
             let decorations = appstate.data.decorations.clone();
             let weapon_slots = appstate.settings.weapon_slots;
@@ -756,19 +1688,71 @@ pub fn update(appstate: &mut State, message: Message) -> Task<Message> {
                             } else {
                                 0
                             },
+                        id: appstate.data.skill_ids.get(&skills[*index].name_attribute),
                     })
                 })
                 .collect_vec();
             let village_rank = appstate.settings.village_rank;
             let sex = appstate.settings.sex as i32;
             let weapon_type = appstate.settings.weapon_type as i32;
-            let gathering_hall_rank = appstate.settings.gathering_hall_rank as i32;
+            let game = appstate.flags.game.clone();
+            let gathering_hall_rank = appstate.settings.hunter_rank.min(game.all_ranks());
+            let max_torso_up = if game.torso_up && appstate.settings.torso_up {
+                appstate.settings.max_torso_up
+            } else {
+                0
+            };
             let trim_count = appstate.settings.trim_count;
             let language = appstate.settings.language;
             let components = appstate.flags.components.clone();
             let excluded = appstate.settings.parts_excluded.clone();
-            let mut progress_sender = appstate.progress_sender.clone().unwrap();
+            //pins of pieces that aren't available anymore would select an unrelated piece
+            let charm_names = appstate
+                .data
+                .charms
+                .iter()
+                .map(Charm::to_string)
+                .collect_vec();
+            let (parts_pinned, missing_pins): (Vec<_>, Vec<_>) =
+                take(&mut appstate.settings.parts_pinned)
+                    .into_iter()
+                    .partition(|(armor_type, name)| {
+                        let parts = match armor_type {
+                            Some(ArmorType::Head) => &head,
+                            Some(ArmorType::Chest) => &body,
+                            Some(ArmorType::Arms) => &arms,
+                            Some(ArmorType::Waist) => &waist,
+                            Some(ArmorType::Legs) => &legs,
+                            None => return charm_names.contains(name),
+                        };
+                        parts.iter().any(|part| &part.name == name)
+                    });
+            appstate.settings.parts_pinned = parts_pinned;
+            for (_, name) in missing_pins {
+                appstate.notices.push(
+                    appstate
+                        .strings
+                        .format("%1 is not available anymore and was unpinned", &[&name]),
+                );
+            }
+            let pinned = |armor_type| {
+                appstate
+                    .settings
+                    .parts_pinned
+                    .iter()
+                    .find(|(t, _)| *t == armor_type)
+                    .map(|(_, name)| name.clone())
+            };
+            let pinned_head = pinned(Some(ArmorType::Head));
+            let pinned_body = pinned(Some(ArmorType::Chest));
+            let pinned_arms = pinned(Some(ArmorType::Arms));
+            let pinned_waist = pinned(Some(ArmorType::Waist));
+            let pinned_legs = pinned(Some(ArmorType::Legs));
+            let pinned_charm = pinned(None);
+            let progress_sender = appstate.progress_sender.clone().unwrap();
             let min_rarity = appstate.settings.min_rarity;
+            let skill_ids = appstate.data.skill_ids.clone();
+            let decoration_cache = appstate.decoration_cache.clone();
             Task::perform(
                 async move {
                     let mut additional_skills = Vec::new();
@@ -783,6 +1767,7 @@ pub fn update(appstate: &mut State, message: Message) -> Task<Message> {
                             requirements_mut.push(Requirement {
                                 name: skill.name_attribute.clone(),
                                 points: skill.points,
+                                id: skill_ids.get(&skill.name_attribute),
                             });
                         }
                         let mut progress_sender = progress_sender.clone();
@@ -803,10 +1788,13 @@ pub fn update(appstate: &mut State, message: Message) -> Task<Message> {
                             &requirements_mut,
                             &decorations.1.clone().into_iter().cloned().collect_vec(),
                         );
-                        let helper_function = |part| {
+                        let helper_function = |part, pinned: &Option<String>| {
                             trim(
                                 part,
                                 |p: &Armor| {
+                                    if let Some(pinned) = pinned {
+                                        return &p.name == pinned;
+                                    }
                                     p.is_valid(
                                         gathering_hall_rank,
                                         village_rank,
@@ -823,18 +1811,63 @@ pub fn update(appstate: &mut State, message: Message) -> Task<Message> {
                                 trim_count,
                             )
                         };
-                        let head = helper_function(&head);
-                        let body = helper_function(&body);
-                        let arms = helper_function(&arms);
-                        let waist = helper_function(&waist);
-                        let legs = helper_function(&legs);
+                        let head = helper_function(&head, &pinned_head);
+                        let body = helper_function(&body, &pinned_body);
+                        let arms = helper_function(&arms, &pinned_arms);
+                        let waist = helper_function(&waist, &pinned_waist);
+                        let legs = helper_function(&legs, &pinned_legs);
                         let charms = trim(
                             &charms,
-                            |charm| charm.is_valid(&requirements_mut),
+                            |charm| {
+                                pinned_charm.as_ref().map_or_else(
+                                    || charm.is_valid(&requirements_mut),
+                                    |pinned| &charm.to_string() == pinned,
+                                )
+                            },
                             &requirements_mut,
                             &scores,
                             trim_count,
                         );
+                        let torso_up_allowed = [
+                            pinned_head.is_none(),
+                            pinned_arms.is_none(),
+                            pinned_waist.is_none(),
+                            pinned_legs.is_none(),
+                        ];
+                        if matches!(msg, Message::SearchIdealCharm) {
+                            let candidates = Charm::candidates(
+                                &requirements_mut,
+                                game.max_slots,
+                                game.max_charm_points,
+                            );
+                            let charm = find_ideal_charm(
+                                &head.1,
+                                &body.1,
+                                &arms.1,
+                                &waist.1,
+                                &legs.1,
+                                &candidates,
+                                &decorations.1,
+                                &requirements_mut,
+                                weapon_slots,
+                                torso_up_allowed,
+                                max_torso_up,
+                                &decoration_cache,
+                                &ideal_charm_cancelled,
+                                |progress| {
+                                    if !ideal_charm_cancelled.load(Ordering::Relaxed) {
+                                        progress_sender
+                                            .try_send(Progress::IdealCharm(progress))
+                                            .unwrap();
+                                    }
+                                },
+                            );
+                            return if ideal_charm_cancelled.load(Ordering::Relaxed) {
+                                FindResults::IdealCharmCancelled
+                            } else {
+                                FindResults::IdealCharm(charm)
+                            };
+                        }
                         let mut results = find(
                             &head.1,
                             &body.1,
@@ -845,11 +1878,14 @@ pub fn update(appstate: &mut State, message: Message) -> Task<Message> {
                             &decorations.1,
                             &requirements_mut,
                             weapon_slots,
+                            torso_up_allowed,
+                            max_torso_up,
                             if matches!(msg, Message::SearchAdditionalSkills) {
                                 1
                             } else {
                                 200
                             },
+                            &decoration_cache,
                         );
                         for result in results.iter_mut() {
                             result.head_index =
@@ -874,9 +1910,10 @@ pub fn update(appstate: &mut State, message: Message) -> Task<Message> {
                         }
                         requirements_mut.pop();
                         let progress = (100.0 * index as f32) / skills.len() as f32;
-                        progress_sender.try_send(Some(progress)).unwrap();
+                        progress_sender
+                            .try_send(Progress::AdditionalSkills(progress))
+                            .unwrap();
                     }
-                    progress_sender.try_send(None).unwrap();
                     FindResults::Skills(additional_skills)
                 },
                 Message::SetsFound,
@@ -884,7 +1921,16 @@ pub fn update(appstate: &mut State, message: Message) -> Task<Message> {
         }
         Message::FontLoaded(_) => Task::none(),
         Message::SetProgress(progress) => {
-            appstate.search_status_skills = SearchStatus::Searching(progress);
+            match progress {
+                Progress::AdditionalSkills(progress) => {
+                    appstate.search_status_skills = SearchStatus::Searching(progress)
+                }
+                Progress::IdealCharm(progress) => {
+                    if let Some(SearchStatus::Searching(_)) = appstate.ideal_charm {
+                        appstate.ideal_charm = Some(SearchStatus::Searching(progress));
+                    }
+                }
+            }
             Task::none()
         }
         Message::SetProgressSender(sender) => {
@@ -905,13 +1951,144 @@ pub fn update(appstate: &mut State, message: Message) -> Task<Message> {
                 .collect();
             appstate.ui_state = UIState::Armor(ArmorState {
                 pieces,
-                skill: RelicArmorSkill::Slots(0),
-                weapon_type: WeaponType::Both,
-                armor_type: ArmorType::default(),
-                name: "".to_owned(),
+                ..Default::default()
             });
             Task::none()
         }
+        Message::ImportCharms => {
+            appstate.ui_state = UIState::Import(ImportState::default());
+            Task::none()
+        }
+        Message::SetImportPath(path) => {
+            if let UIState::Import(ref mut state) = appstate.ui_state {
+                state.path = path;
+                state.confirm_overwrite = false;
+            }
+            Task::none()
+        }
+        Message::ImportLoad => {
+            if let UIState::Import(ref mut state) = appstate.ui_state {
+                if is_share_code(&state.path) {
+                    let attributes = appstate
+                        .data
+                        .skills
+                        .iter()
+                        .map(|skill| &skill.name_attribute)
+                        .unique()
+                        .collect_vec();
+                    state.rejected.clear();
+                    state.charms =
+                        parse_share_code(&state.path, &attributes).unwrap_or_else(|| {
+                            state.rejected.push(state.path.clone());
+                            Vec::new()
+                        });
+                    return Task::none();
+                }
+                match std::fs::read_to_string(&state.path) {
+                    Ok(list) => {
+                        let aliases = skill_aliases(&appstate.flags.skills, &appstate.data.skills);
+                        (state.charms, state.rejected) =
+                            parse_charms(&list, &aliases, &appstate.flags.game);
+                    }
+                    Err(error) => {
+                        state.charms.clear();
+                        state.rejected = vec![error.to_string()];
+                    }
+                }
+            }
+            Task::none()
+        }
+        Message::ExportShareCode => {
+            if let UIState::Import(ref mut state) = appstate.ui_state {
+                let attributes = appstate
+                    .data
+                    .skills
+                    .iter()
+                    .map(|skill| &skill.name_attribute)
+                    .unique()
+                    .collect_vec();
+                state.path = share_code(&appstate.data.charms, &attributes);
+                state.status = appstate
+                    .strings
+                    .get("Share code copied to the clipboard")
+                    .to_owned();
+                return iced::clipboard::write(state.path.clone());
+            }
+            Task::none()
+        }
+        msg @ (Message::ExportFile | Message::ExportFileOverwrite) => {
+            if let UIState::Import(ref mut state) = appstate.ui_state {
+                if matches!(msg, Message::ExportFile) && Path::new(&state.path).exists() {
+                    state.confirm_overwrite = true;
+                    return Task::none();
+                }
+                state.confirm_overwrite = false;
+                //same skill names as mycharms.txt so the file can be read in every language
+                let result = write_charms(Path::new(&state.path), &appstate.flags.charms);
+                state.status = match result {
+                    Ok(()) => appstate
+                        .strings
+                        .format("%1 charms exported", &[&appstate.data.charms.len()]),
+                    Err(error) => error.to_string(),
+                };
+            }
+            Task::none()
+        }
+        Message::DecorationCacheCleared => {
+            appstate.decoration_cache.clear();
+            Task::none()
+        }
+        Message::NoticesDismissed => {
+            appstate.notices.clear();
+            Task::none()
+        }
+        Message::LoadErrorsDismissed => {
+            appstate.load_errors.clear();
+            Task::none()
+        }
+        Message::SkillTypeSelected(index) => {
+            appstate.settings.skill_type_index = index;
+            Task::none()
+        }
+        Message::PresetSelected(name) => {
+            if let Some(preset) = appstate
+                .settings
+                .presets
+                .iter()
+                .find(|preset| preset.name == name)
+                .cloned()
+            {
+                appstate.settings.apply_preset(preset);
+            }
+            Task::none()
+        }
+        Message::PresetNameChanged(name) => {
+            appstate.settings.preset_name = name;
+            Task::none()
+        }
+        Message::PresetSave => {
+            let preset = appstate
+                .settings
+                .to_preset(appstate.settings.preset_name.clone());
+            match appstate
+                .settings
+                .presets
+                .iter_mut()
+                .find(|p| p.name == preset.name)
+            {
+                Some(existing) => *existing = preset,
+                None => appstate.settings.presets.push(preset),
+            }
+            Task::none()
+        }
+        Message::PresetDelete => {
+            let name = take(&mut appstate.settings.preset_name);
+            appstate
+                .settings
+                .presets
+                .retain(|preset| preset.name != name);
+            Task::none()
+        }
         Message::SetArmorName(name) => {
             if let UIState::Armor(ref mut state) = appstate.ui_state {
                 state.name = name;
@@ -924,16 +2101,54 @@ pub fn update(appstate: &mut State, message: Message) -> Task<Message> {
             }
             Task::none()
         }
+        Message::SetRelicDefenceMin(defence) => {
+            if let UIState::Armor(ref mut state) = appstate.ui_state {
+                state.defence_min = defence;
+                state.defence_max = state.defence_max.max(defence);
+            }
+            Task::none()
+        }
+        Message::SetRelicDefenceMax(defence) => {
+            if let UIState::Armor(ref mut state) = appstate.ui_state {
+                state.defence_max = defence;
+                state.defence_min = state.defence_min.min(defence);
+            }
+            Task::none()
+        }
+        Message::SetRelicResistance(index, resistance) => {
+            if let UIState::Armor(ref mut state) = appstate.ui_state {
+                state.resistances[index] = resistance;
+            }
+            Task::none()
+        }
+        Message::CustomEdit(index) => {
+            if let UIState::Armor(ref mut state) = appstate.ui_state {
+                state.edit(index);
+            }
+            Task::none()
+        }
     }
 }
 
 pub fn view(appstate: &State) -> iced::Element<'_, Message, Theme> {
-    row![
+    let strings = &appstate.strings;
+    let torso_up = strings.get("torso up");
+    let skill_type = appstate
+        .skill_types
+        .get(appstate.settings.skill_type_index)
+        .map_or("All", String::as_str);
+    let related = (skill_type == "Related").then(|| appstate.related_attributes());
+    let skill_type_labels = appstate
+        .skill_types
+        .iter()
+        .map(|skill_type| strings.get(skill_type).to_owned())
+        .collect_vec();
+    let content = row![
         match &appstate.ui_state {
             //general stuff aka rank, type, sex, etc
             UIState::Default => column![
                 row![
-                    text("Language"),
+                    text(strings.get("Language")),
                     horizontal_space(),
                     pick_list(
                         Language::all(),
@@ -943,27 +2158,27 @@ pub fn view(appstate: &State) -> iced::Element<'_, Message, Theme> {
                     .text_shaping(text::Shaping::Advanced),
                 ]
                 .width(Length::Fixed(230f32)),
-                row![
-                    text("Gathering Hall"),
-                    horizontal_space(),
-                    pick_list(
-                        GatheringHallRank::all(),
-                        Some(appstate.settings.gathering_hall_rank),
-                        Message::GatheringHallRankSelected
-                    ),
-                ]
+                row![text(strings.get("Gathering Hall")), horizontal_space(), {
+                    let ranks = appstate.flags.game.hunter_rank_options(strings.get("All"));
+                    let selected = ranks
+                        .iter()
+                        .find(|rank| rank.rank >= appstate.settings.hunter_rank)
+                        .or(ranks.last())
+                        .cloned();
+                    pick_list(ranks, selected, Message::HunterRankSelected)
+                },]
                 .width(Length::Fill),
                 row![
-                    text("Village Rank"),
+                    text(strings.get("Village Rank")),
                     horizontal_space(),
                     pick_list(
-                        (1..=10).collect_vec(),
+                        (1..=appstate.flags.game.village_ranks).collect_vec(),
                         Some(appstate.settings.village_rank),
                         Message::VillageRankSelected
                     ),
                 ],
                 row![
-                    text("Minimum Armor Rarity"),
+                    text(strings.get("Minimum Armor Rarity")),
                     horizontal_space(),
                     pick_list(
                         (1..=10).collect_vec(),
@@ -971,16 +2186,35 @@ pub fn view(appstate: &State) -> iced::Element<'_, Message, Theme> {
                         Message::SetMinRarity
                     ),
                 ],
+                checkbox(
+                    strings.get("Include every possible relic armor"),
+                    appstate.settings.possible_relics
+                )
+                .on_toggle(Message::PossibleRelicsToggled),
+                if appstate.flags.game.torso_up {
+                    row![
+                        checkbox(strings.get("Torso up pieces"), appstate.settings.torso_up)
+                            .on_toggle(Message::TorsoUpToggled),
+                        horizontal_space(),
+                        pick_list(
+                            (1..=4).collect_vec(),
+                            Some(appstate.settings.max_torso_up),
+                            Message::MaxTorsoUpSelected
+                        ),
+                    ]
+                } else {
+                    row![]
+                },
                 row![
-                    text("Max Weapon Slots"),
+                    text(strings.get("Max Weapon Slots")),
                     horizontal_space(),
                     pick_list(
-                        (0..=3).collect_vec(),
+                        (0..=appstate.flags.game.max_slots).collect_vec(),
                         Some(appstate.settings.weapon_slots as i32),
                         Message::SetSlots
                     ),
                 ],
-                text("Weapon Skill"),
+                text(strings.get("Weapon Skill")),
                 {
                     let row = Row::with_children([combo_box(
                         &appstate.weapon_skills,
@@ -989,7 +2223,7 @@ pub fn view(appstate: &State) -> iced::Element<'_, Message, Theme> {
                         Message::WeaponSkillSelected,
                     )
                     .into()]);
-                    if let Some(Requirement { name: _, points }) = &appstate.settings.weapon_skill {
+                    if let Some(Requirement { points, .. }) = &appstate.settings.weapon_skill {
                         row.push(number_input(points, 0..=6, Message::WeaponPointsSelected))
                     } else {
                         row
@@ -998,14 +2232,14 @@ pub fn view(appstate: &State) -> iced::Element<'_, Message, Theme> {
                 text(""),
                 row![
                     segmented_button(
-                        text(Sex::Male.to_string()),
+                        text(strings.get(&Sex::Male.to_string()).to_owned()),
                         Sex::Male,
                         Some(appstate.settings.sex),
                         Message::SexSelected
                     )
                     .width(Length::Fill),
                     segmented_button(
-                        text(Sex::Female.to_string()),
+                        text(strings.get(&Sex::Female.to_string()).to_owned()),
                         Sex::Female,
                         Some(appstate.settings.sex),
                         Message::SexSelected
@@ -1014,23 +2248,23 @@ pub fn view(appstate: &State) -> iced::Element<'_, Message, Theme> {
                 ],
                 row![
                     segmented_button(
-                        text(WeaponType::Melee.to_string()),
+                        text(strings.get(&WeaponType::Melee.to_string()).to_owned()),
                         WeaponType::Melee,
                         Some(appstate.settings.weapon_type),
                         Message::WeaponTypeSelected
                     )
                     .width(Length::Fill),
                     segmented_button(
-                        text(WeaponType::Marksman.to_string()),
+                        text(strings.get(&WeaponType::Marksman.to_string()).to_owned()),
                         WeaponType::Marksman,
                         Some(appstate.settings.weapon_type),
                         Message::WeaponTypeSelected
                     )
                     .width(Length::Fill),
                 ],
-                text(format!(
-                    "Considered Parts per Slot: {}",
-                    appstate.settings.trim_count
+                text(strings.format(
+                    "Considered Parts per Slot: %1",
+                    &[&appstate.settings.trim_count]
                 )),
                 slider(
                     15.0..=60.0,
@@ -1038,13 +2272,15 @@ pub fn view(appstate: &State) -> iced::Element<'_, Message, Theme> {
                     Message::TrimCountChanged
                 ),
                 text(""),
-                button(text("Edit Charms")).on_press(Message::EditCharms),
-                button(text("Edit Relics")).on_press(Message::EditRelics),
+                button(text(strings.get("Edit Charms"))).on_press(Message::EditCharms),
+                button(text(strings.get("Edit Relics"))).on_press(Message::EditRelics),
+                button(text(strings.get("Import/Export Charms"))).on_press(Message::ImportCharms),
                 text(""),
                 if !appstate.settings.parts_excluded.is_empty() {
                     column![
                         row![
-                            text("Excluded parts\n").width(Length::Fill),
+                            text(format!("{}\n", strings.get("Excluded parts")))
+                                .width(Length::Fill),
                             button(text("🗑️").shaping(text::Shaping::Advanced))
                                 .on_press(Message::PartsExcludedClear)
                                 .style(button::danger),
@@ -1063,14 +2299,52 @@ pub fn view(appstate: &State) -> iced::Element<'_, Message, Theme> {
                     ]
                 } else {
                     column![]
+                },
+                if !appstate.settings.parts_pinned.is_empty() {
+                    column![
+                        row![
+                            text(format!("{}\n", strings.get("Pinned parts"))).width(Length::Fill),
+                            button(text("🗑️").shaping(text::Shaping::Advanced))
+                                .on_press(Message::PartsPinnedClear)
+                                .style(button::danger),
+                        ],
+                        scrollable(appstate.settings.parts_pinned.iter().enumerate().fold(
+                            Column::new().padding(Padding::ZERO.right(SCROLLBAR_WIDTH)),
+                            |col, (index, (_, name))| {
+                                col.push(row![
+                                    text(name)
+                                        .shaping(text::Shaping::Advanced)
+                                        .width(Length::Fill),
+                                    button(text("🗑️").shaping(text::Shaping::Advanced))
+                                        .on_press(Message::PartPinnedRemoved(index))
+                                        .style(button::danger),
+                                ])
+                            },
+                        )),
+                    ]
+                } else {
+                    column![]
                 }
             ]
             .width(Length::Shrink),
             _ => column![],
         },
         column![
-            text("Available Skills"),
-            text_input("search", &appstate.settings.skill_filter)
+            text(strings.get("Available Skills")),
+            pick_list(
+                skill_type_labels.clone(),
+                skill_type_labels
+                    .get(appstate.settings.skill_type_index)
+                    .cloned(),
+                move |label| Message::SkillTypeSelected(
+                    skill_type_labels
+                        .iter()
+                        .position(|x| *x == label)
+                        .unwrap_or_default()
+                )
+            )
+            .width(Length::Fixed(200f32)),
+            text_input(strings.get("search"), &appstate.settings.skill_filter)
                 .on_input(Message::SkillFilterChanged)
                 .width(Length::Fixed(200f32)),
             scrollable(
@@ -1086,7 +2360,19 @@ pub fn view(appstate: &State) -> iced::Element<'_, Message, Theme> {
                         UIState::Charms(ref state) =>
                             state.skills.iter().all(|req| &&req.name != name),
                         UIState::Armor(ref state) =>
-                            &state.skill.get_skill_name() != name && skills[0].is_relic_skill(),
+                            state
+                                .skill
+                                .as_ref()
+                                .is_none_or(|skill| &&skill.name != name)
+                                && skills[0].is_relic_skill(),
+                        UIState::Import(_) => false,
+                    })
+                    .filter(|(name, skills)| match (skill_type, &related) {
+                        (_, Some(related)) => related.contains(name.as_str()),
+                        ("Misc", _) => skills.iter().any(|skill| skill.has_category(None)),
+                        (skill_type, _) => skills
+                            .iter()
+                            .any(|skill| skill.has_category(Some(skill_type))),
                     })
                     .filter_map(|(name, skills)| {
                         let text = format!("{name} {}", {
@@ -1099,16 +2385,24 @@ pub fn view(appstate: &State) -> iced::Element<'_, Message, Theme> {
                             buffer
                         });
                         contains_fuzzy(&text, &appstate.settings.skill_filter)
-                            .then_some((name, text))
+                            .then_some((name, text, skills))
                     })
                     .fold(
                         Column::new().padding(Padding::ZERO.right(SCROLLBAR_WIDTH)),
-                        |col, (name, button_text)| col.push(
+                        |col, (name, button_text, skills)| col.push(with_description(
                             button(text(button_text))
                                 .on_press(Message::AvailableSkillSelected(name.clone()))
                                 .style(button::secondary)
-                                .width(Length::Fill)
-                        )
+                                .width(Length::Fill),
+                            skills
+                                .iter()
+                                .filter(|skill| skill.points > 0)
+                                .filter_map(|skill| skill
+                                    .description
+                                    .as_ref()
+                                    .map(|description| format!("{}: {description}", skill.name)))
+                                .join("\n")
+                        ))
                     )
             )
         ]
@@ -1116,8 +2410,44 @@ pub fn view(appstate: &State) -> iced::Element<'_, Message, Theme> {
         match appstate.ui_state {
             UIState::Default => column![
                 row![
-                    text("Required skills").width(Length::Fill),
-                    button("clear").on_press(Message::ClearRequiredSkills)
+                    pick_list(
+                        appstate
+                            .settings
+                            .presets
+                            .iter()
+                            .map(|preset| preset.name.clone())
+                            .collect_vec(),
+                        appstate
+                            .settings
+                            .presets
+                            .iter()
+                            .find(|preset| preset.name == appstate.settings.preset_name)
+                            .map(|preset| preset.name.clone()),
+                        Message::PresetSelected
+                    )
+                    .placeholder(strings.get("Presets")),
+                    text_input(strings.get("Preset name"), &appstate.settings.preset_name)
+                        .on_input(Message::PresetNameChanged)
+                        .on_submit(Message::PresetSave),
+                    button(strings.get("save")).on_press_maybe(
+                        (!appstate.settings.preset_name.is_empty()).then_some(Message::PresetSave)
+                    ),
+                    button(text("🗑️").shaping(text::Shaping::Advanced))
+                        .on_press_maybe(
+                            appstate
+                                .settings
+                                .presets
+                                .iter()
+                                .any(|preset| preset.name == appstate.settings.preset_name)
+                                .then_some(Message::PresetDelete)
+                        )
+                        .style(button::danger),
+                ]
+                .spacing(5)
+                .align_y(iced::Alignment::Center),
+                row![
+                    text(strings.get("Required skills")).width(Length::Fill),
+                    button(strings.get("clear")).on_press(Message::ClearRequiredSkills)
                 ]
                 .align_y(iced::Alignment::Center),
                 scrollable(
@@ -1131,14 +2461,16 @@ pub fn view(appstate: &State) -> iced::Element<'_, Message, Theme> {
                                         .iter()
                                         .enumerate()
                                         .filter(|(_, skill)| skill.points > 0)
-                                        .map(|(skill_index, skill)| segmented_button(
-                                            text(&skill.name),
-                                            skill_index,
-                                            Some(*active_skill_index),
-                                            |x| Message::NeededSkillIndexChanged(index, x)
-                                        )
-                                        .width(Length::Fill)
-                                        .into())
+                                        .map(|(skill_index, skill)| with_description(
+                                            segmented_button(
+                                                text(&skill.name),
+                                                skill_index,
+                                                Some(*active_skill_index),
+                                                |x| Message::NeededSkillIndexChanged(index, x)
+                                            )
+                                            .width(Length::Fill),
+                                            appstate.skill_description(skill)
+                                        ))
                                         .collect_vec()
                                 )
                                 .align_y(iced::Alignment::Center)
@@ -1156,10 +2488,11 @@ pub fn view(appstate: &State) -> iced::Element<'_, Message, Theme> {
                 ),
                 text(""),
                 row![
-                    text("Possible additional skills").width(Length::Fill),
+                    text(strings.get("Possible additional skills")).width(Length::Fill),
                     match &appstate.search_status_skills {
-                        SearchStatus::Found(_) =>
-                            Element::from(button("find").on_press(Message::SearchAdditionalSkills)),
+                        SearchStatus::Found(_) => Element::from(
+                            button(strings.get("find")).on_press(Message::SearchAdditionalSkills)
+                        ),
                         SearchStatus::Searching(progress) =>
                             progress_bar(0.0..=100.0, *progress).into(),
                     }
@@ -1167,7 +2500,7 @@ pub fn view(appstate: &State) -> iced::Element<'_, Message, Theme> {
                 match &appstate.search_status_skills {
                     SearchStatus::Found(results) =>
                         if results.is_empty() {
-                            column![text("None")].align_x(iced::Alignment::Center)
+                            column![text(strings.get("None"))].align_x(iced::Alignment::Center)
                         } else {
                             column![scrollable(
                                 Column::with_children(results.iter().map(|&result| {
@@ -1186,19 +2519,23 @@ pub fn view(appstate: &State) -> iced::Element<'_, Message, Theme> {
                             .width(Length::Fill)]
                         },
                     SearchStatus::Searching(_) =>
-                        column![text("Searching...")].align_x(iced::Alignment::Center),
+                        column![text(strings.get("Searching..."))].align_x(iced::Alignment::Center),
                 }
             ]
             .width(Length::FillPortion(6)),
             UIState::Charms(ref state) => column![
                 row![
-                    text("Slots"),
-                    number_input(&state.slots, 0..=3, Message::SetSlots)
+                    text(strings.get("Slots")),
+                    number_input(
+                        &state.slots,
+                        0..=appstate.flags.game.max_slots,
+                        Message::SetSlots
+                    )
                 ],
                 text(""),
                 row![
-                    text("Skills").width(Length::Fill),
-                    button("clear").on_press(Message::ClearRequiredSkills)
+                    text(strings.get("Skills")).width(Length::Fill),
+                    button(strings.get("clear")).on_press(Message::ClearRequiredSkills)
                 ],
                 Column::with_children(state.skills.iter().enumerate().map(|(index, req)| {
                     row![
@@ -1216,13 +2553,14 @@ pub fn view(appstate: &State) -> iced::Element<'_, Message, Theme> {
             .width(Length::FillPortion(4)),
             UIState::Armor(ref state) => column![
                 row![
-                    text("Name"),
+                    text(strings.get("Name")),
                     horizontal_space(),
-                    text_input("Some Piece", &state.name).on_input(Message::SetArmorName)
+                    text_input(strings.get("Some Piece"), &state.name)
+                        .on_input(Message::SetArmorName)
                 ],
                 Row::with_children(ArmorType::all().iter().map(|&t| {
                     segmented_button(
-                        text(t.to_string()),
+                        text(strings.get(&t.to_string()).to_owned()),
                         t,
                         Some(state.armor_type),
                         Message::SetArmorType,
@@ -1231,53 +2569,162 @@ pub fn view(appstate: &State) -> iced::Element<'_, Message, Theme> {
                 })),
                 row![
                     segmented_button(
-                        text(WeaponType::Both.to_string()),
+                        text(strings.get(&WeaponType::Both.to_string()).to_owned()),
                         WeaponType::Both,
                         Some(state.weapon_type),
                         Message::WeaponTypeSelected
                     ),
                     segmented_button(
-                        text(WeaponType::Melee.to_string()),
+                        text(strings.get(&WeaponType::Melee.to_string()).to_owned()),
                         WeaponType::Melee,
                         Some(state.weapon_type),
                         Message::WeaponTypeSelected
                     ),
                     segmented_button(
-                        text(WeaponType::Marksman.to_string()),
+                        text(strings.get(&WeaponType::Marksman.to_string()).to_owned()),
                         WeaponType::Marksman,
                         Some(state.weapon_type),
                         Message::WeaponTypeSelected
                     ),
                 ],
                 match &state.skill {
-                    RelicArmorSkill::Slots(slots) => row![
-                        number_input(slots, 0..=3, Message::SetSlots),
-                        text("Slots").width(Length::Fill)
-                    ],
-                    RelicArmorSkill::Skill(requirement) => row![
-                        number_input(&requirement.points, 0..=6, |x| {
-                            Message::CustomSkillSetPoints(0, x)
-                        }),
+                    Some(requirement) => row![
+                        number_input(
+                            &requirement.points,
+                            0..=Skill::max_relic_armor_points_of(
+                                &appstate.data.skills,
+                                &requirement.name
+                            )
+                            .unwrap_or_default(),
+                            |x| Message::CustomSkillSetPoints(0, x)
+                        ),
                         text(&requirement.name).width(Length::Fill),
                         button(text("🗑️").shaping(text::Shaping::Advanced))
                             .on_press(Message::NeededSkillDeleted(0))
                             .style(button::danger),
                     ],
-                }
+                    None => row![text(strings.get("Select a skill on the left"))],
+                },
+                row![
+                    number_input(
+                        &state.slots,
+                        0..=appstate.flags.game.max_slots,
+                        Message::SetSlots
+                    ),
+                    text(strings.get("Slots")).width(Length::Fill)
+                ],
+                row![
+                    number_input(
+                        &state.defence_min,
+                        RELIC_DEFENCE,
+                        Message::SetRelicDefenceMin
+                    ),
+                    text(strings.get("Base Defence")).width(Length::Fill)
+                ],
+                row![
+                    number_input(
+                        &state.defence_max,
+                        RELIC_DEFENCE,
+                        Message::SetRelicDefenceMax
+                    ),
+                    text(strings.get("Max Defence")).width(Length::Fill)
+                ],
+                Column::with_children(
+                    [
+                        "Fire Res",
+                        "Water Res",
+                        "Thunder Res",
+                        "Ice Res",
+                        "Dragon Res"
+                    ]
+                    .into_iter()
+                    .zip(state.resistances)
+                    .enumerate()
+                    .map(|(index, (label, resistance))| {
+                        row![
+                            number_input(&resistance, RELIC_RESISTANCE, move |x| {
+                                Message::SetRelicResistance(index, x)
+                            }),
+                            text(strings.get(label)).width(Length::Fill)
+                        ]
+                        .into()
+                    })
+                )
+            ]
+            .width(Length::FillPortion(4)),
+            UIState::Import(ref state) => column![
+                text(strings.get("Charm list file (Athena's ASS or asf format) or share code")),
+                text_input("mycharms.txt", &state.path)
+                    .on_input(Message::SetImportPath)
+                    .on_submit(Message::ImportLoad),
+                row![
+                    button(text(strings.get("Load"))).on_press(Message::ImportLoad),
+                    button(text(strings.get("Export to file"))).on_press_maybe(
+                        (!state.path.is_empty() && !is_share_code(&state.path))
+                            .then_some(Message::ExportFile)
+                    ),
+                    button(text(strings.get("Copy share code"))).on_press(Message::ExportShareCode),
+                ]
+                .spacing(5),
+                if state.confirm_overwrite {
+                    row![
+                        text(strings.format("%1 exists already", &[&state.path]))
+                            .style(text::danger),
+                        button(text(strings.get("Overwrite")))
+                            .on_press(Message::ExportFileOverwrite)
+                            .style(button::danger),
+                    ]
+                    .spacing(5)
+                    .align_y(iced::Alignment::Center)
+                } else {
+                    row![]
+                },
+                text(&state.status),
+                text(""),
+                if state.rejected.is_empty() {
+                    column![]
+                } else {
+                    column![
+                        text(strings.get("Lines that could not be read")).style(text::danger),
+                        scrollable(Column::with_children(
+                            state.rejected.iter().map(|line| text(line).into())
+                        ))
+                    ]
+                },
             ]
             .width(Length::FillPortion(4)),
         },
         match appstate.ui_state {
             UIState::Default => match &appstate.search_status {
                 SearchStatus::Found(results) => column![
-                    button(text("find")).on_press(Message::Search),
-                    text(format!("{} results", results.len())),
+                    button(text(strings.get("find"))).on_press(Message::Search),
+                    text(strings.format("%1 results", &[&results.len()])),
+                    match &appstate.ideal_charm {
+                        _ if !results.is_empty() => column![],
+                        None => column![button(text(
+                            strings.get("Find a charm that would lead to results")
+                        ))
+                        .on_press(Message::SearchIdealCharm)],
+                        Some(SearchStatus::Searching(progress)) => column![row![
+                            progress_bar(0.0..=100.0, *progress),
+                            button(text(strings.get("Cancel")))
+                                .on_press(Message::IdealCharmCancelled),
+                        ]
+                        .spacing(5)],
+                        Some(SearchStatus::Found(charms)) => match charms.first() {
+                            Some(charm) => column![
+                                text(strings.get("This charm would lead to results:")),
+                                text(charm.to_string()).shaping(text::Shaping::Advanced),
+                            ],
+                            None => column![text(strings.get("No charm leads to results"))],
+                        },
+                    },
                     //maybe add sorting
                     scrollable(
                         Column::with_children(
                             results
                                 .iter()
-                                .map(|result| {
+                                .map(|(result, skill_totals)| {
                                     let torso_up_count = [
                                         result.head_index,
                                         result.waist_index,
@@ -1288,65 +2735,24 @@ pub fn view(appstate: &State) -> iced::Element<'_, Message, Theme> {
                                     .filter(|x| x.is_none())
                                     .count();
                                     let head =
-                                        result.head_index.map_or("torso up".to_owned(), |index| {
-                                            appstate
-                                                .data
-                                                .head
-                                                .get(index)
-                                                .unwrap_or_else(|| {
-                                                    &appstate.data.relic_head
-                                                        [index - appstate.data.head.len()]
-                                                })
-                                                .name
-                                                .clone()
+                                        result.head_index.map_or(torso_up.to_owned(), |index| {
+                                            appstate.data.part(ArmorType::Head, index).name.clone()
                                         });
                                     let waist =
-                                        result.waist_index.map_or("torso up".to_owned(), |index| {
-                                            appstate
-                                                .data
-                                                .waist
-                                                .get(index)
-                                                .unwrap_or_else(|| {
-                                                    &appstate.data.relic_waist
-                                                        [index - appstate.data.waist.len()]
-                                                })
-                                                .name
-                                                .clone()
+                                        result.waist_index.map_or(torso_up.to_owned(), |index| {
+                                            appstate.data.part(ArmorType::Waist, index).name.clone()
                                         });
                                     let arms =
-                                        result.arms_index.map_or("torso up".to_owned(), |index| {
-                                            appstate
-                                                .data
-                                                .arms
-                                                .get(index)
-                                                .unwrap_or_else(|| {
-                                                    &appstate.data.relic_arms
-                                                        [index - appstate.data.arms.len()]
-                                                })
-                                                .name
-                                                .clone()
+                                        result.arms_index.map_or(torso_up.to_owned(), |index| {
+                                            appstate.data.part(ArmorType::Arms, index).name.clone()
                                         });
                                     let legs =
-                                        result.legs_index.map_or("torso up".to_owned(), |index| {
-                                            appstate
-                                                .data
-                                                .legs
-                                                .get(index)
-                                                .unwrap_or_else(|| {
-                                                    &appstate.data.relic_legs
-                                                        [index - appstate.data.legs.len()]
-                                                })
-                                                .name
-                                                .clone()
+                                        result.legs_index.map_or(torso_up.to_owned(), |index| {
+                                            appstate.data.part(ArmorType::Legs, index).name.clone()
                                         });
                                     let body = appstate
                                         .data
-                                        .body
-                                        .get(result.body_index)
-                                        .unwrap_or_else(|| {
-                                            &appstate.data.relic_body
-                                                [result.body_index - appstate.data.body.len()]
-                                        })
+                                        .part(ArmorType::Chest, result.body_index)
                                         .name
                                         .clone();
                                     let charm =
@@ -1354,11 +2760,36 @@ pub fn view(appstate: &State) -> iced::Element<'_, Message, Theme> {
                                     // let decorations_data = appstate.data.decorations.read().unwrap();
                                     let set = [head, body, arms, waist, legs, charm]
                                         .into_iter()
-                                        .fold(Column::new(), |col, part| {
+                                        .zip([
+                                            Some(ArmorType::Head),
+                                            Some(ArmorType::Chest),
+                                            Some(ArmorType::Arms),
+                                            Some(ArmorType::Waist),
+                                            Some(ArmorType::Legs),
+                                            None,
+                                        ])
+                                        .fold(Column::new(), |col, (part, armor_type)| {
                                             let mut row = row![text(part.clone())
                                                 .shaping(text::Shaping::Advanced)
                                                 .width(Length::Fill)];
-                                            if &part != "torso up" && !part.contains(",") {
+                                            if part != torso_up {
+                                                let pinned = appstate
+                                                    .settings
+                                                    .parts_pinned
+                                                    .contains(&(armor_type, part.clone()));
+                                                row = row.push(
+                                                    button(
+                                                        text("📌").shaping(text::Shaping::Advanced),
+                                                    )
+                                                    .on_press_maybe((!pinned).then(|| {
+                                                        Message::PartPinned(
+                                                            armor_type,
+                                                            part.clone(),
+                                                        )
+                                                    })),
+                                                );
+                                            }
+                                            if part != torso_up && !part.contains(",") {
                                                 row = row.push(
                                                     button(
                                                         text("🗑️").shaping(text::Shaping::Advanced),
@@ -1396,7 +2827,45 @@ pub fn view(appstate: &State) -> iced::Element<'_, Message, Theme> {
                                                         ))
                                                         .style(button::danger)
                                                     ])
-                                                }),
+                                                })
+                                                .push(skill_totals.iter().fold(
+                                                    Column::new().padding(Padding::ZERO.top(5)),
+                                                    |col, total| {
+                                                        let label = match &total.activated {
+                                                            Some(skill) => format!(
+                                                                "{} ({} {:+})",
+                                                                skill.name,
+                                                                total.attribute,
+                                                                total.points
+                                                            ),
+                                                            None => format!(
+                                                                "{} {:+}",
+                                                                total.attribute, total.points
+                                                            ),
+                                                        };
+                                                        col.push(with_description(
+                                                            text(label).size(12).style(
+                                                                match &total.activated {
+                                                                    Some(skill)
+                                                                        if skill.points < 0 =>
+                                                                    {
+                                                                        text::danger
+                                                                    }
+                                                                    Some(_) => text::success,
+                                                                    None => text::default,
+                                                                },
+                                                            ),
+                                                            total
+                                                                .activated
+                                                                .as_ref()
+                                                                .map(|skill| {
+                                                                    appstate
+                                                                        .skill_description(skill)
+                                                                })
+                                                                .unwrap_or_default(),
+                                                        ))
+                                                    },
+                                                )),
                                         )
                                         .style(container::bordered_box)
                                         .into(),
@@ -1410,27 +2879,27 @@ pub fn view(appstate: &State) -> iced::Element<'_, Message, Theme> {
                     )
                 ],
                 SearchStatus::Searching(_) =>
-                    column![text("Searching...")].align_x(iced::Alignment::Center),
+                    column![text(strings.get("Searching..."))].align_x(iced::Alignment::Center),
             }
             .width(Length::Fixed(280f32)),
             UIState::Charms(ref state) => column![
-                button(text("Add")).on_press_maybe(
+                button(text(strings.get("Add"))).on_press_maybe(
                     (state.skills.first().is_some_and(|x| x.points != 0)
                         && state.skills.len() <= 2)
                         .then_some(Message::CustomAdd)
                 ),
                 text(""),
                 row![
-                    button(text("Abort"))
+                    button(text(strings.get("Abort")))
                         .on_press(Message::Abort)
                         .width(Length::Fill),
-                    button(text("Save"))
+                    button(text(strings.get("Save")))
                         .on_press(Message::CustomSave)
                         .width(Length::Fill),
                 ],
                 text(""),
-                row![text("Charms"),],
-                text_input("search", &appstate.settings.skill_filter)
+                row![text(strings.get("Charms")),],
+                text_input(strings.get("search"), &appstate.settings.skill_filter)
                     .on_input(Message::SkillFilterChanged),
                 scrollable(Column::with_children(
                     state
@@ -1453,20 +2922,24 @@ pub fn view(appstate: &State) -> iced::Element<'_, Message, Theme> {
             ]
             .width(Length::Fixed(280f32)),
             UIState::Armor(ref state) => column![
-                button(text("Add"))
-                    .on_press_maybe((!state.skill.is_empty()).then_some(Message::CustomAdd)),
+                button(text(strings.get(if state.editing.is_some() {
+                    "Replace"
+                } else {
+                    "Add"
+                })))
+                .on_press_maybe((!state.is_empty()).then_some(Message::CustomAdd)),
                 text(""),
                 row![
-                    button(text("Abort"))
+                    button(text(strings.get("Abort")))
                         .on_press(Message::Abort)
                         .width(Length::Fill),
-                    button(text("Save"))
+                    button(text(strings.get("Save")))
                         .on_press(Message::CustomSave)
                         .width(Length::Fill),
                 ],
                 text(""),
-                text("Pieces"),
-                text_input("search", &appstate.settings.skill_filter)
+                text(strings.get("Pieces")),
+                text_input(strings.get("search"), &appstate.settings.skill_filter)
                     .on_input(Message::SkillFilterChanged),
                 scrollable(Column::with_children(
                     state
@@ -1474,13 +2947,15 @@ pub fn view(appstate: &State) -> iced::Element<'_, Message, Theme> {
                         .iter()
                         .enumerate()
                         .filter(|&(_index, piece)| contains_fuzzy(
-                            &ArmorState::piece_to_string(piece),
+                            &ArmorState::piece_to_string(piece, strings),
                             &appstate.settings.skill_filter
                         ))
                         .map(|(index, piece)| row![
-                            text(ArmorState::piece_to_string(piece))
+                            text(ArmorState::piece_to_string(piece, strings))
                                 .width(Length::Fill)
                                 .shaping(text::Shaping::Advanced),
+                            button(text("✏️").shaping(text::Shaping::Advanced))
+                                .on_press(Message::CustomEdit(index)),
                             button(text("🗑️").shaping(text::Shaping::Advanced))
                                 .on_press(Message::CustomRemove(index))
                                 .style(button::danger),
@@ -1490,8 +2965,126 @@ pub fn view(appstate: &State) -> iced::Element<'_, Message, Theme> {
                 ))
             ]
             .width(Length::Fixed(280f32)),
+            UIState::Import(ref state) => column![
+                row![
+                    button(text(strings.get("Abort")))
+                        .on_press(Message::Abort)
+                        .width(Length::Fill),
+                    button(text(strings.get("Save")))
+                        .on_press(Message::CustomSave)
+                        .width(Length::Fill),
+                ],
+                text(""),
+                text(strings.format("%1 charms", &[&state.charms.len()])),
+                scrollable(Column::with_children(state.charms.iter().enumerate().map(
+                    |(index, charm)| {
+                        row![
+                            text(charm.to_string()).width(Length::Fill),
+                            button(text("🗑️").shaping(text::Shaping::Advanced))
+                                .on_press(Message::CustomRemove(index))
+                                .style(button::danger),
+                        ]
+                        .padding(Padding::ZERO.right(SCROLLBAR_WIDTH))
+                        .into()
+                    }
+                ))),
+            ]
+            .width(Length::Fixed(280f32)),
         }
     ]
-    .spacing(10)
-    .into()
+    .spacing(10);
+    let load_errors = (!appstate.load_errors.is_empty()).then(|| {
+        container(column![
+            row![
+                text(strings.get("Some files could not be read, the data is incomplete"))
+                    .style(text::danger)
+                    .width(Length::Fill),
+                button(text(strings.get("Dismiss"))).on_press(Message::LoadErrorsDismissed),
+            ]
+            .align_y(iced::Alignment::Center),
+            scrollable(Column::with_children(
+                appstate
+                    .load_errors
+                    .iter()
+                    .map(|error| text(error.to_string()).size(12).into())
+            ))
+            .height(Length::Shrink),
+        ])
+        .padding(5)
+        .style(container::bordered_box)
+    });
+    let notices = (!appstate.notices.is_empty()).then(|| {
+        container(column![row![
+            Column::with_children(
+                appstate
+                    .notices
+                    .iter()
+                    .map(|notice| text(notice).shaping(text::Shaping::Advanced).into())
+            )
+            .width(Length::Fill),
+            button(text(strings.get("Dismiss"))).on_press(Message::NoticesDismissed),
+        ]
+        .align_y(iced::Alignment::Center),])
+        .padding(5)
+        .style(container::bordered_box)
+    });
+    let debug_panel = appstate.debug_panel.then(|| {
+        let stats = appstate.decoration_cache.stats();
+        container(
+            row![
+                text(strings.format(
+                    "Decoration cache: %1 hits, %2 misses (%3% hit rate), %4/%5 entries",
+                    &[
+                        &stats.hits,
+                        &stats.misses,
+                        &format!("{:.1}", 100.0 * stats.hit_rate()),
+                        &stats.len,
+                        &stats.capacity,
+                    ],
+                ))
+                .size(12)
+                .width(Length::Fill),
+                button(strings.get("clear")).on_press(Message::DecorationCacheCleared),
+            ]
+            .align_y(iced::Alignment::Center),
+        )
+        .padding(5)
+        .style(container::bordered_box)
+    });
+    column![]
+        .push_maybe(load_errors)
+        .push_maybe(notices)
+        .push_maybe(debug_panel)
+        .push(content)
+        .spacing(10)
+        .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn migrate(json: serde_json::Value) -> (Settings, u64) {
+        Settings::migrate(json).unwrap()
+    }
+
+    #[test]
+    fn every_settings_version_has_a_migration() {
+        assert_eq!(MIGRATIONS.len() as u64, SETTINGS_VERSION - 1);
+    }
+
+    #[test]
+    fn settings_migrate_to_the_current_version() {
+        let (settings, version) = migrate(serde_json::json!({"gathering_hall_rank": "G1"}));
+        assert_eq!((settings.hunter_rank, version), (9, 1));
+        let (settings, version) =
+            migrate(serde_json::json!({"version": 2, "gathering_hall_rank": "HR3"}));
+        assert_eq!((settings.hunter_rank, version), (3, 2));
+        let (settings, _) = migrate(serde_json::json!({"version": 2}));
+        assert_eq!(settings.hunter_rank, GatheringHallRank::All as i32);
+        let (settings, version) = migrate(
+            serde_json::json!({"version": 3, "hunter_rank": 5, "gathering_hall_rank": "G1"}),
+        );
+        assert_eq!((settings.hunter_rank, version), (5, 3));
+    }
 }
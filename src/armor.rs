@@ -1,9 +1,14 @@
 use serde::{Deserialize, Serialize};
 
-use crate::{component::Component, requirements::Requirement, skillpoint::SkillPoint};
+use crate::{
+    component::{Component, MaterialCount},
+    requirements::Requirement,
+    skill_id::{SkillIds, SkillPointsById},
+    skillpoint::{SkillPoint, SkillPoints},
+};
 
 #[derive(Serialize, Debug, PartialEq, Eq, Hash, Deserialize, Clone, Default)]
-#[serde(default)]
+#[serde(from = "ArmorRow", into = "ArmorRow")]
 pub struct Armor {
     pub name: String,
     pub sex: i32,
@@ -19,27 +24,130 @@ pub struct Armor {
     pub defence_thunder: i32,
     pub defence_ice: i32,
     pub defence_dragon: i32,
-    pub ability_1_name: String,
-    pub ability_1_points: Option<i32>,
-    pub ability_2_name: String,
-    pub ability_2_points: Option<i32>,
-    pub ability_3_name: String,
-    pub ability_3_points: Option<i32>,
-    pub ability_4_name: String,
-    pub ability_4_points: Option<i32>,
-    pub ability_5_name: String,
-    pub ability_5_points: Option<i32>,
-    pub material_1_name: String,
-    pub material_1_count: Option<i32>,
-    pub material_2_name: String,
-    pub material_2_count: Option<i32>,
-    pub material_3_name: String,
-    pub material_3_count: Option<i32>,
-    pub material_4_name: String,
-    pub material_4_count: Option<i32>,
+    pub skills: Vec<SkillPoints>,
+    pub materials: Vec<MaterialCount>,
+    /// Points of `skills` by skill id, filled by `intern_skills`
+    pub points_by_id: SkillPointsById,
+}
+
+/// Layout of the armor csv files, also used for the relics in the settings
+#[derive(Serialize, Deserialize, Default)]
+#[serde(default)]
+struct ArmorRow {
+    name: String,
+    sex: i32,
+    weapon_type: i32,
+    rarity: i32,
+    slots: i32,
+    hunter_rank: i32,
+    village_rank: i32,
+    defence_min: i32,
+    defence_max: i32,
+    defence_fire: i32,
+    defence_water: i32,
+    defence_thunder: i32,
+    defence_ice: i32,
+    defence_dragon: i32,
+    ability_1_name: String,
+    ability_1_points: Option<i32>,
+    ability_2_name: String,
+    ability_2_points: Option<i32>,
+    ability_3_name: String,
+    ability_3_points: Option<i32>,
+    ability_4_name: String,
+    ability_4_points: Option<i32>,
+    ability_5_name: String,
+    ability_5_points: Option<i32>,
+    material_1_name: String,
+    material_1_count: Option<i32>,
+    material_2_name: String,
+    material_2_count: Option<i32>,
+    material_3_name: String,
+    material_3_count: Option<i32>,
+    material_4_name: String,
+    material_4_count: Option<i32>,
+}
+
+impl From<ArmorRow> for Armor {
+    fn from(row: ArmorRow) -> Self {
+        Armor {
+            name: row.name,
+            sex: row.sex,
+            weapon_type: row.weapon_type,
+            rarity: row.rarity,
+            slots: row.slots,
+            hunter_rank: row.hunter_rank,
+            village_rank: row.village_rank,
+            defence_min: row.defence_min,
+            defence_max: row.defence_max,
+            defence_fire: row.defence_fire,
+            defence_water: row.defence_water,
+            defence_thunder: row.defence_thunder,
+            defence_ice: row.defence_ice,
+            defence_dragon: row.defence_dragon,
+            skills: SkillPoints::from_columns([
+                (row.ability_1_name, row.ability_1_points),
+                (row.ability_2_name, row.ability_2_points),
+                (row.ability_3_name, row.ability_3_points),
+                (row.ability_4_name, row.ability_4_points),
+                (row.ability_5_name, row.ability_5_points),
+            ]),
+            materials: MaterialCount::from_columns([
+                (row.material_1_name, row.material_1_count),
+                (row.material_2_name, row.material_2_count),
+                (row.material_3_name, row.material_3_count),
+                (row.material_4_name, row.material_4_count),
+            ]),
+            points_by_id: Default::default(),
+        }
+    }
+}
+
+impl From<Armor> for ArmorRow {
+    fn from(armor: Armor) -> Self {
+        let [ability_1, ability_2, ability_3, ability_4, ability_5] =
+            SkillPoints::to_columns(armor.skills);
+        let [material_1, material_2, material_3, material_4] =
+            MaterialCount::to_columns(armor.materials);
+        ArmorRow {
+            name: armor.name,
+            sex: armor.sex,
+            weapon_type: armor.weapon_type,
+            rarity: armor.rarity,
+            slots: armor.slots,
+            hunter_rank: armor.hunter_rank,
+            village_rank: armor.village_rank,
+            defence_min: armor.defence_min,
+            defence_max: armor.defence_max,
+            defence_fire: armor.defence_fire,
+            defence_water: armor.defence_water,
+            defence_thunder: armor.defence_thunder,
+            defence_ice: armor.defence_ice,
+            defence_dragon: armor.defence_dragon,
+            ability_1_name: ability_1.0,
+            ability_1_points: ability_1.1,
+            ability_2_name: ability_2.0,
+            ability_2_points: ability_2.1,
+            ability_3_name: ability_3.0,
+            ability_3_points: ability_3.1,
+            ability_4_name: ability_4.0,
+            ability_4_points: ability_4.1,
+            ability_5_name: ability_5.0,
+            ability_5_points: ability_5.1,
+            material_1_name: material_1.0,
+            material_1_count: material_1.1,
+            material_2_name: material_2.0,
+            material_2_count: material_2.1,
+            material_3_name: material_3.0,
+            material_3_count: material_3.1,
+            material_4_name: material_4.0,
+            material_4_count: material_4.1,
+        }
+    }
 }
 
 impl Armor {
+    #[allow(clippy::too_many_arguments)]
     pub fn is_valid(
         &self,
         hr: i32,
@@ -63,7 +171,7 @@ impl Armor {
                 return true;
             }
             for requirement in requirements {
-                if self.points(&requirement.name) > 0 {
+                if self.points(requirement.id) > 0 {
                     return true;
                 }
             }
@@ -71,60 +179,31 @@ impl Armor {
         }
     }
     pub fn japanese(&self, components: &[Component]) -> bool {
-        [
-            &self.material_1_name,
-            &self.material_2_name,
-            &self.material_3_name,
-            &self.material_4_name,
-        ]
-        .into_iter()
-        .any(|name| {
-            components
-                .iter()
-                .find(|&c| &c.name == name)
-                .map(|c| c.japanese())
-                .unwrap_or(false)
-        })
+        MaterialCount::japanese(&self.materials, components)
     }
 }
 
 impl SkillPoint for Armor {
-    fn points(&self, ability: &str) -> i32 {
-        if ability == self.ability_5_name {
-            self.ability_5_points
-        } else if ability == self.ability_4_name {
-            self.ability_4_points
-        } else if ability == self.ability_3_name {
-            self.ability_3_points
-        } else if ability == self.ability_2_name {
-            self.ability_2_points
-        } else if ability == self.ability_1_name {
-            self.ability_1_points
-        } else {
-            Some(0)
-        }
-        .unwrap_or(0)
+    fn points_by_id(&self) -> &SkillPointsById {
+        &self.points_by_id
     }
     fn slots(&self) -> i32 {
         self.slots
     }
 
+    fn intern_skills(&mut self, ids: &SkillIds) {
+        self.points_by_id = ids.intern(&self.skills);
+    }
+
     fn translate_skills(&mut self, translation: &std::collections::HashMap<String, String>) {
-        if let Some(t) = translation.get(&self.ability_1_name) {
-            self.ability_1_name.clone_from(t);
-        }
-        if let Some(t) = translation.get(&self.ability_2_name) {
-            self.ability_2_name.clone_from(t);
-        }
-        if let Some(t) = translation.get(&self.ability_3_name) {
-            self.ability_3_name.clone_from(t);
-        }
-        if let Some(t) = translation.get(&self.ability_4_name) {
-            self.ability_4_name.clone_from(t);
-        }
-        if let Some(t) = translation.get(&self.ability_5_name) {
-            self.ability_5_name.clone_from(t);
-        }
+        SkillPoints::translate(&mut self.skills, translation);
+    }
+
+    fn skill_names(&self) -> Vec<&str> {
+        self.skills
+            .iter()
+            .map(|skill| skill.name.as_str())
+            .collect()
     }
 
     fn max_defence(&self) -> u32 {
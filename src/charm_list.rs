@@ -0,0 +1,281 @@
+use std::collections::HashMap;
+
+use itertools::Itertools;
+
+use crate::{charms::Charm, game::Game, loading::read_lines, paths::language_dirs, skill::Skill};
+
+/// Maps the lowercase skill attribute names of every language in `Languages`
+/// to the attribute names of `translated`. `base` and `translated` have to be in the same order.
+pub fn skill_aliases(base: &[Skill], translated: &[Skill]) -> HashMap<String, String> {
+    let attributes = base
+        .iter()
+        .zip(translated)
+        .map(|(base, translated)| (&base.name_attribute, &translated.name_attribute))
+        .unique_by(|(base, _)| *base)
+        .collect_vec();
+    let mut aliases = HashMap::new();
+    for (base, translated) in &attributes {
+        aliases.insert(base.to_lowercase(), (*translated).clone());
+        aliases.insert(translated.to_lowercase(), (*translated).clone());
+    }
+    for language in language_dirs() {
+        let Ok(lines) = read_lines(&language.join("skills.txt")) else {
+            continue;
+        };
+        for (line, (_, translated)) in lines
+            .into_iter()
+            .take_while(|line| !line.is_empty())
+            .zip(&attributes)
+        {
+            aliases
+                .entry(line.trim_start_matches('\u{feff}').trim().to_lowercase())
+                .or_insert_with(|| (*translated).clone());
+        }
+    }
+    aliases
+}
+
+/// Parses charm lists like the ones of Athena's ASS ("slots,skill,points,skill,points").
+/// Lines that can't be read or have more slots than `game` allows are returned as well.
+pub fn parse_charms(
+    text: &str,
+    aliases: &HashMap<String, String>,
+    game: &Game,
+) -> (Vec<Charm>, Vec<String>) {
+    let mut charms = Vec::new();
+    let mut rejected = Vec::new();
+    for line in text
+        .lines()
+        .map(|line| line.trim_start_matches('\u{feff}').trim())
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        //the first row of the files asf writes is an empty charm
+        .filter(|line| {
+            !line
+                .split(',')
+                .all(|field| field.is_empty() || field == "0")
+        })
+    {
+        match parse_charm(line, aliases, game) {
+            Some(charm) => charms.push(charm),
+            None => rejected.push(line.to_owned()),
+        }
+    }
+    (charms, rejected)
+}
+
+fn parse_charm(line: &str, aliases: &HashMap<String, String>, game: &Game) -> Option<Charm> {
+    let mut slots = None;
+    let mut skills = Vec::new();
+    let mut fields = line
+        .split([',', ';', '\t'])
+        .map(|field| field.trim().trim_matches('"'))
+        .filter(|field| !field.is_empty());
+    while let Some(field) = fields.next() {
+        if let Ok(number) = field.parse::<i32>() {
+            if slots.replace(number).is_some() {
+                return None;
+            }
+        } else {
+            let skill = aliases.get(&field.to_lowercase())?;
+            let points = fields.next()?.trim_start_matches('+').parse::<i32>().ok()?;
+            skills.push((skill.clone(), points));
+        }
+    }
+    let slots = slots.unwrap_or(0);
+    if !(0..=game.max_slots).contains(&slots)
+        || skills.len() > 2
+        || (skills.is_empty() && slots == 0)
+    {
+        return None;
+    }
+    let mut skills = skills.into_iter();
+    let (skill_1, points_1) = skills.next().unwrap_or_default();
+    let skill_2 = skills.next();
+    Some(Charm {
+        slots,
+        skill_1,
+        points_1,
+        points_2: skill_2.as_ref().map(|(_, points)| *points),
+        skill_2: skill_2.map(|(name, _)| name).unwrap_or_default(),
+        ..Default::default()
+    })
+}
+
+const SHARE_CODE_PREFIX: &str = "asf1:";
+const BASE64: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+/// Encodes charms language independently as indices into `attributes`,
+/// the unique skill attributes in data order
+pub fn share_code(charms: &[Charm], attributes: &[&String]) -> String {
+    let attribute_index = |name: &str| {
+        attributes
+            .iter()
+            .position(|attribute| *attribute == name)
+            .map_or(0, |index| index as u8 + 1)
+    };
+    let bytes = charms
+        .iter()
+        .flat_map(|charm| {
+            [
+                charm.slots as u8,
+                attribute_index(&charm.skill_1),
+                charm.points_1 as i8 as u8,
+                charm
+                    .points_2
+                    .map_or(0, |_| attribute_index(&charm.skill_2)),
+                charm.points_2.unwrap_or(0) as i8 as u8,
+            ]
+        })
+        .collect_vec();
+    let mut code = SHARE_CODE_PREFIX.to_owned();
+    for chunk in bytes.chunks(3) {
+        let value = chunk.iter().enumerate().fold(0u32, |value, (i, &byte)| {
+            value | (byte as u32) << (16 - 8 * i)
+        });
+        for i in 0..=chunk.len() {
+            code.push(BASE64[(value >> (18 - 6 * i) & 63) as usize] as char);
+        }
+    }
+    code
+}
+
+pub fn is_share_code(text: &str) -> bool {
+    text.trim().starts_with(SHARE_CODE_PREFIX)
+}
+
+pub fn parse_share_code(code: &str, attributes: &[&String]) -> Option<Vec<Charm>> {
+    let digits = code
+        .trim()
+        .strip_prefix(SHARE_CODE_PREFIX)?
+        .bytes()
+        .map(|c| BASE64.iter().position(|&b| b == c).map(|d| d as u32))
+        .collect::<Option<Vec<_>>>()?;
+    let mut bytes = Vec::new();
+    for chunk in digits.chunks(4) {
+        let value = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |value, (i, &digit)| value | digit << (18 - 6 * i));
+        for i in 0..chunk.len().saturating_sub(1) {
+            bytes.push((value >> (16 - 8 * i)) as u8);
+        }
+    }
+    let attribute = |index: u8| match index {
+        0 => Some(String::new()),
+        index => attributes.get(index as usize - 1).map(|a| (*a).clone()),
+    };
+    bytes
+        .chunks(5)
+        .map(|charm| {
+            let &[slots, skill_1, points_1, skill_2, points_2] = charm else {
+                return None;
+            };
+            Some(Charm {
+                slots: slots as i32,
+                skill_1: attribute(skill_1)?,
+                points_1: points_1 as i8 as i32,
+                skill_2: attribute(skill_2)?,
+                points_2: (skill_2 != 0).then_some(points_2 as i8 as i32),
+                ..Default::default()
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn aliases() -> HashMap<String, String> {
+        [("attack", "Attack"), ("guard", "Guard"), ("攻撃", "Attack")]
+            .into_iter()
+            .map(|(alias, name)| (alias.to_owned(), name.to_owned()))
+            .collect()
+    }
+
+    fn charm(slots: i32, skill_1: &str, points_1: i32, skill_2: Option<(&str, i32)>) -> Charm {
+        Charm {
+            slots,
+            skill_1: skill_1.to_owned(),
+            points_1,
+            skill_2: skill_2.map_or(String::new(), |(name, _)| name.to_owned()),
+            points_2: skill_2.map(|(_, points)| points),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn parses_ass_charm_lists() {
+        let text = "\u{feff}#slots,skill,points,skill,points\n\
+            2,Attack,5,Guard,-3\n\
+            \n\
+            0;\"攻撃\";+4\n\
+            3\n\
+            1\tguard\t2\n";
+        let (charms, rejected) = parse_charms(text, &aliases(), &Game::default());
+        assert_eq!(
+            charms,
+            [
+                charm(2, "Attack", 5, Some(("Guard", -3))),
+                charm(0, "Attack", 4, None),
+                charm(3, "", 0, None),
+                charm(1, "Guard", 2, None),
+            ]
+        );
+        assert!(rejected.is_empty());
+    }
+
+    #[test]
+    fn skips_the_empty_first_row_of_asf_files() {
+        let (charms, rejected) =
+            parse_charms("0,,0,,\n1,Attack,2,,\n", &aliases(), &Game::default());
+        assert_eq!(charms, [charm(1, "Attack", 2, None)]);
+        assert!(rejected.is_empty());
+    }
+
+    #[test]
+    fn share_codes_round_trip() {
+        let attack = "Attack".to_owned();
+        let guard = "Guard".to_owned();
+        let attributes = [&attack, &guard];
+        let charms = [
+            charm(0, "Attack", 7, None),
+            charm(1, "Guard", -3, Some(("Attack", 4))),
+            charm(2, "Attack", 1, Some(("Guard", -10))),
+            charm(3, "", 0, None),
+            charm(3, "Guard", 13, None),
+        ];
+        for count in 0..=charms.len() {
+            let code = share_code(&charms[..count], &attributes);
+            assert!(is_share_code(&code));
+            assert_eq!(
+                parse_share_code(&code, &attributes).unwrap(),
+                &charms[..count]
+            );
+        }
+        assert_eq!(parse_share_code("asf1:!", &attributes), None);
+    }
+
+    #[test]
+    fn rejects_unreadable_lines() {
+        let text = "4,Attack,1\n\
+            1,Unknown,3\n\
+            1,Attack\n\
+            1,Attack,1,Guard,1,Attack,1\n\
+            1,2,Attack,1";
+        let (charms, rejected) = parse_charms(text, &aliases(), &Game::default());
+        assert!(charms.is_empty());
+        assert_eq!(rejected, text.lines().collect_vec());
+    }
+
+    #[test]
+    fn rejects_more_slots_than_the_game_has() {
+        let game = Game {
+            max_slots: 2,
+            ..Default::default()
+        };
+        let (charms, rejected) = parse_charms("2,Attack,1\n3,Attack,1\n", &aliases(), &game);
+        assert_eq!(charms, [charm(2, "Attack", 1, None)]);
+        assert_eq!(rejected, ["3,Attack,1"]);
+    }
+}
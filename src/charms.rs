@@ -1,6 +1,11 @@
+use itertools::Itertools;
 use serde::{Deserialize, Serialize};
 
-use crate::{requirements::Requirement, skillpoint::SkillPoint};
+use crate::{
+    requirements::Requirement,
+    skill_id::{SkillIds, SkillPointsById},
+    skillpoint::{SkillPoint, SkillPoints},
+};
 
 #[derive(Debug, PartialEq, Eq, Hash, Default, Deserialize, Serialize, Clone)]
 #[serde(default)]
@@ -10,22 +15,25 @@ pub struct Charm {
     pub points_1: i32,
     pub skill_2: String,
     pub points_2: Option<i32>,
+    #[serde(skip)]
+    pub points_by_id: SkillPointsById,
 }
 
 impl SkillPoint for Charm {
-    fn points(&self, ability: &str) -> i32 {
-        if ability == self.skill_1 {
-            self.points_1 
-        } else if ability == self.skill_2 {
-            self.points_2.unwrap_or(0)
-        } else {
-            0
-        }
+    fn points_by_id(&self) -> &SkillPointsById {
+        &self.points_by_id
     }
     fn slots(&self) -> i32 {
         self.slots
     }
 
+    fn intern_skills(&mut self, ids: &SkillIds) {
+        self.points_by_id = ids.intern(&[
+            SkillPoints::new(&self.skill_1, self.points_1),
+            SkillPoints::new(&self.skill_2, self.points_2.unwrap_or(0)),
+        ]);
+    }
+
     fn translate_skills(&mut self, translation: &std::collections::HashMap<String, String>) {
         if let Some(t) = translation.get(&self.skill_1) {
             self.skill_1.clone_from(t);
@@ -35,6 +43,14 @@ impl SkillPoint for Charm {
         }
     }
 
+    fn skill_names(&self) -> Vec<&str> {
+        [&self.skill_1, &self.skill_2]
+            .into_iter()
+            .filter(|name| !name.is_empty())
+            .map(String::as_str)
+            .collect()
+    }
+
     fn max_defence(&self) -> u32 {
         0
     }
@@ -44,42 +60,158 @@ impl SkillPoint for Charm {
     }
 }
 impl Charm {
-    
     pub fn is_valid(&self, requirements: &[Requirement]) -> bool {
         if self.slots() == 3 {
             return true;
         }
         for requirement in requirements {
-            if self.points(&requirement.name) > 0 {
+            if self.points(requirement.id) > 0 {
                 return true;
             }
         }
         false
     }
+
+    /// Every charm with up to two of the required skills, sorted by `cost`.
+    /// `max_slots` and `max_points` come from the `Game` of the data pack
+    pub fn candidates(requirements: &[Requirement], max_slots: i32, max_points: i32) -> Vec<Charm> {
+        let requirements = requirements
+            .iter()
+            .filter(|req| req.points > 0)
+            .collect_vec();
+        let mut charms = Vec::new();
+        for slots in 0..=max_slots {
+            charms.push(Charm {
+                slots,
+                ..Default::default()
+            });
+            for (index, req_1) in requirements.iter().enumerate() {
+                for points_1 in 1..=req_1.points.min(max_points) {
+                    charms.push(Charm {
+                        slots,
+                        skill_1: req_1.name.clone(),
+                        points_1,
+                        points_by_id: SkillPointsById::new(&[(req_1.id, points_1)]),
+                        ..Default::default()
+                    });
+                    for req_2 in requirements.iter().skip(index + 1) {
+                        for points_2 in 1..=req_2.points.min(max_points) {
+                            charms.push(Charm {
+                                slots,
+                                skill_1: req_1.name.clone(),
+                                points_1,
+                                skill_2: req_2.name.clone(),
+                                points_2: Some(points_2),
+                                points_by_id: SkillPointsById::new(&[
+                                    (req_1.id, points_1),
+                                    (req_2.id, points_2),
+                                ]),
+                            });
+                        }
+                    }
+                }
+            }
+        }
+        charms.sort_by_key(Charm::cost);
+        charms
+    }
+    /// Same slots and skill points, no matter in which order the skills are
+    pub fn is_equivalent(&self, other: &Charm) -> bool {
+        let skills = |charm: &Charm| {
+            [
+                (charm.skill_1.clone(), charm.points_1),
+                (charm.skill_2.clone(), charm.points_2.unwrap_or(0)),
+            ]
+            .into_iter()
+            .filter(|(name, points)| !name.is_empty() && *points != 0)
+            .sorted()
+            .collect_vec()
+        };
+        self.slots == other.slots && skills(self) == skills(other)
+    }
+    /// Rough measure of how hard a charm is to get
+    pub fn cost(&self) -> i32 {
+        self.slots + self.points_1 + self.points_2.unwrap_or(0)
+    }
 }
 
 impl std::fmt::Display for Charm {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let ret = write!(
-            f,
-            "{} {}",
-            self.skill_1,
-            self.points_1,
-        );
+        let ret = if self.skill_1.is_empty() {
+            Ok(())
+        } else {
+            write!(f, "{} {}", self.skill_1, self.points_1,)
+        };
         if let Some(points_2) = self.points_2 {
-            write!(
-                f,
-                ", {} {}",
-                self.skill_2,
-                points_2
-                )?;
+            write!(f, ", {} {}", self.skill_2, points_2)?;
         }
         for _ in 0..(self.slots) {
-            write!(
-                f,
-                " ○"
-        )?;
+            write!(f, " ○")?;
         }
         ret
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn requirement(name: &str, points: i32) -> Requirement {
+        Requirement {
+            name: name.to_owned(),
+            points,
+            id: Default::default(),
+        }
+    }
+
+    #[test]
+    fn candidates_are_sorted_and_limited() {
+        let requirements = [
+            requirement("Attack", 20),
+            requirement("Guard", 2),
+            requirement("Evasion", 0),
+        ];
+        let candidates = Charm::candidates(&requirements, 2, 7);
+        assert!(candidates
+            .windows(2)
+            .all(|pair| pair[0].cost() <= pair[1].cost()));
+        assert_eq!(candidates[0], Charm::default());
+        assert!(candidates.iter().all(|charm| charm.slots <= 2
+            && charm.points_1 <= 7
+            && charm.points_2.unwrap_or(0) <= 2
+            && charm.skill_1 != "Evasion"
+            && charm.skill_2 != "Evasion"));
+        //empty, Attack, Guard and both for every slot count
+        assert_eq!(candidates.len(), 3 * (1 + 7 + 2 + 7 * 2));
+        assert!(candidates.contains(&Charm {
+            slots: 2,
+            skill_1: "Attack".to_owned(),
+            points_1: 7,
+            skill_2: "Guard".to_owned(),
+            points_2: Some(2),
+            points_by_id: SkillPointsById::new(&[(Default::default(), 7), (Default::default(), 2)]),
+        }));
+    }
+    #[test]
+    fn equivalent_charms_ignore_the_skill_order() {
+        let charm = |slots, skill_1: &str, points_1, skill_2: &str, points_2| Charm {
+            slots,
+            skill_1: skill_1.to_owned(),
+            points_1,
+            skill_2: skill_2.to_owned(),
+            points_2,
+            ..Default::default()
+        };
+        let attack_guard = charm(1, "Attack", 4, "Guard", Some(-2));
+        assert!(attack_guard.is_equivalent(&charm(1, "Guard", -2, "Attack", Some(4))));
+        assert!(!attack_guard.is_equivalent(&charm(2, "Guard", -2, "Attack", Some(4))));
+        assert!(!attack_guard.is_equivalent(&charm(1, "Attack", 4, "Guard", Some(-3))));
+        assert!(charm(0, "Attack", 4, "", None).is_equivalent(&charm(
+            0,
+            "Attack",
+            4,
+            "Guard",
+            Some(0)
+        )));
+    }
+}
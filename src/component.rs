@@ -1,4 +1,4 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
 #[derive(Default, Clone, Deserialize, Debug)]
 #[serde(default)]
@@ -14,5 +14,51 @@ impl Component {
     pub fn event(&self) -> bool {
         self.special == Some("Event".to_owned()) || self.japanese()
     }
+}
+
+/// An amount of a material needed to craft a part
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Hash, Clone, Default)]
+pub struct MaterialCount {
+    pub name: String,
+    pub count: i32,
+}
+
+impl MaterialCount {
+    /// Reads the name and count columns of a csv row, empty columns are left out
+    pub fn from_columns<const N: usize>(columns: [(String, Option<i32>); N]) -> Vec<Self> {
+        columns
+            .into_iter()
+            .filter(|(name, _)| !name.is_empty())
+            .map(|(name, count)| MaterialCount {
+                name,
+                count: count.unwrap_or(0),
+            })
+            .collect()
+    }
+    /// Fills the name and count columns of a csv row, the materials past `N` are dropped
+    pub fn to_columns<const N: usize>(materials: Vec<Self>) -> [(String, Option<i32>); N] {
+        let mut materials = materials.into_iter();
+        std::array::from_fn(|_| {
+            materials
+                .next()
+                .map(|material| (material.name, Some(material.count)))
+                .unwrap_or_default()
+        })
+    }
+    /// Whether one of the materials is only available in the japanese version
+    pub fn japanese(materials: &[Self], components: &[Component]) -> bool {
+        materials.iter().any(|material| {
+            components
+                .iter()
+                .find(|c| c.name == material.name)
+                .map(|c| c.japanese())
+                .unwrap_or(false)
+        })
+    }
+}
 
+/// One way to craft a part
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Hash, Clone, Default)]
+pub struct Recipe {
+    pub materials: Vec<MaterialCount>,
 }
@@ -1,15 +1,36 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
-use crate::{component::Component, requirements::Requirement, skillpoint::SkillPoint};
+use crate::{
+    component::{Component, MaterialCount, Recipe},
+    requirements::Requirement,
+    skill_id::{SkillIds, SkillPointsById},
+    skillpoint::{SkillPoint, SkillPoints},
+};
 
-#[derive(Default, PartialEq, Eq, Hash, Clone, Deserialize, Debug)]
-#[serde(default)]
+#[derive(Default, PartialEq, Eq, Hash, Clone, Serialize, Deserialize, Debug)]
+#[serde(from = "DecorationRow", into = "DecorationRow")]
 pub struct Decoration {
     pub name: String,
     rarity: i32,
     slots: i32,
     pub hunter_rank: i32,
     pub village_rank: i32,
+    pub skills: Vec<SkillPoints>,
+    /// The a and b recipes, empty ones are left out
+    pub recipes: Vec<Recipe>,
+    /// Points of `skills` by skill id, filled by `intern_skills`
+    points_by_id: SkillPointsById,
+}
+
+/// Layout of decorations.txt
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
+struct DecorationRow {
+    name: String,
+    rarity: i32,
+    slots: i32,
+    hunter_rank: i32,
+    village_rank: i32,
     skill_1_name: String,
     skill_1_points: i32,
     skill_2_name: String,
@@ -31,6 +52,85 @@ pub struct Decoration {
     material_b4_name: String,
     material_b4_count: Option<i32>,
 }
+
+impl From<DecorationRow> for Decoration {
+    fn from(row: DecorationRow) -> Self {
+        let recipes = [
+            MaterialCount::from_columns([
+                (row.material_a1_name, row.material_a1_count),
+                (row.material_a2_name, row.material_a2_count),
+                (row.material_a3_name, row.material_a3_count),
+                (row.material_a4_name, row.material_a4_count),
+            ]),
+            MaterialCount::from_columns([
+                (row.material_b1_name, row.material_b1_count),
+                (row.material_b2_name, row.material_b2_count),
+                (row.material_b3_name, row.material_b3_count),
+                (row.material_b4_name, row.material_b4_count),
+            ]),
+        ]
+        .into_iter()
+        .filter(|materials| !materials.is_empty())
+        .map(|materials| Recipe { materials })
+        .collect();
+        Decoration {
+            name: row.name,
+            rarity: row.rarity,
+            slots: row.slots,
+            hunter_rank: row.hunter_rank,
+            village_rank: row.village_rank,
+            skills: SkillPoints::from_columns([
+                (row.skill_1_name, Some(row.skill_1_points)),
+                (row.skill_2_name, row.skill_2_points),
+            ]),
+            recipes,
+            points_by_id: Default::default(),
+        }
+    }
+}
+
+impl From<Decoration> for DecorationRow {
+    fn from(decoration: Decoration) -> Self {
+        let [(skill_1_name, skill_1_points), (skill_2_name, skill_2_points)] =
+            SkillPoints::to_columns(decoration.skills);
+        let mut recipes = decoration
+            .recipes
+            .into_iter()
+            .map(|recipe| recipe.materials);
+        let [material_a1, material_a2, material_a3, material_a4] =
+            MaterialCount::to_columns(recipes.next().unwrap_or_default());
+        let [material_b1, material_b2, material_b3, material_b4] =
+            MaterialCount::to_columns(recipes.next().unwrap_or_default());
+        DecorationRow {
+            name: decoration.name,
+            rarity: decoration.rarity,
+            slots: decoration.slots,
+            hunter_rank: decoration.hunter_rank,
+            village_rank: decoration.village_rank,
+            skill_1_name,
+            skill_1_points: skill_1_points.unwrap_or(0),
+            skill_2_name,
+            skill_2_points,
+            material_a1_name: material_a1.0,
+            material_a1_count: material_a1.1,
+            material_a2_name: material_a2.0,
+            material_a2_count: material_a2.1,
+            material_a3_name: material_a3.0,
+            material_a3_count: material_a3.1,
+            material_a4_name: material_a4.0,
+            material_a4_count: material_a4.1,
+            material_b1_name: material_b1.0,
+            material_b1_count: material_b1.1,
+            material_b2_name: material_b2.0,
+            material_b2_count: material_b2.1,
+            material_b3_name: material_b3.0,
+            material_b3_count: material_b3.1,
+            material_b4_name: material_b4.0,
+            material_b4_count: material_b4.1,
+        }
+    }
+}
+
 impl Decoration {
     pub fn is_valid(
         &self,
@@ -44,7 +144,7 @@ impl Decoration {
             false
         } else {
             for requirement in requirements {
-                if self.points(&requirement.name) > 0 {
+                if self.points(requirement.id) > 0 {
                     return true;
                 }
             }
@@ -53,28 +153,27 @@ impl Decoration {
     }
 }
 impl SkillPoint for Decoration {
-    fn points(&self, ability: &str) -> i32 {
-        if ability == self.skill_1_name {
-            self.skill_1_points
-        } else if ability == self.skill_2_name {
-            self.skill_2_points.unwrap_or(0)
-        } else {
-            0
-        }
+    fn points_by_id(&self) -> &SkillPointsById {
+        &self.points_by_id
     }
 
     fn slots(&self) -> i32 {
         self.slots
     }
 
+    fn intern_skills(&mut self, ids: &SkillIds) {
+        self.points_by_id = ids.intern(&self.skills);
+    }
 
     fn translate_skills(&mut self, translation: &std::collections::HashMap<String, String>) {
-        if let Some(t) = translation.get(&self.skill_1_name) {
-            self.skill_1_name.clone_from(t);
-        }
-        if let Some(t) = translation.get(&self.skill_2_name) {
-            self.skill_2_name.clone_from(t);
-        }
+        SkillPoints::translate(&mut self.skills, translation);
+    }
+
+    fn skill_names(&self) -> Vec<&str> {
+        self.skills
+            .iter()
+            .map(|skill| skill.name.as_str())
+            .collect()
     }
 
     fn max_defence(&self) -> u32 {
@@ -0,0 +1,50 @@
+use std::path::Path;
+
+#[cfg(feature = "embedded-data")]
+static LANGUAGES: include_dir::Dir = include_dir::include_dir!("$CARGO_MANIFEST_DIR/Languages");
+
+/// The data files of the default game, next to the Languages folder
+#[cfg(feature = "embedded-data")]
+const FILES: &[(&str, &[u8])] = &[
+    ("arms.txt", include_bytes!("../arms.txt")),
+    ("body.txt", include_bytes!("../body.txt")),
+    ("head.txt", include_bytes!("../head.txt")),
+    ("legs.txt", include_bytes!("../legs.txt")),
+    ("waist.txt", include_bytes!("../waist.txt")),
+    ("decorations.txt", include_bytes!("../decorations.txt")),
+    ("skills.txt", include_bytes!("../skills.txt")),
+    ("components.txt", include_bytes!("../components.txt")),
+    ("game.json", include_bytes!("../game.json")),
+];
+
+/// Contents of the embedded file at `path`, relative to the data directory
+#[cfg(feature = "embedded-data")]
+pub fn file(path: &Path) -> Option<&'static [u8]> {
+    match path.strip_prefix("Languages") {
+        Ok(path) => LANGUAGES.get_file(path).map(include_dir::File::contents),
+        Err(_) => FILES
+            .iter()
+            .find(|(name, _)| Path::new(name) == path)
+            .map(|(_, contents)| *contents),
+    }
+}
+
+#[cfg(not(feature = "embedded-data"))]
+pub fn file(_path: &Path) -> Option<&'static [u8]> {
+    None
+}
+
+/// Names of the embedded folders in Languages
+#[cfg(feature = "embedded-data")]
+pub fn languages() -> Vec<String> {
+    LANGUAGES
+        .dirs()
+        .filter_map(|dir| dir.path().to_str())
+        .map(str::to_owned)
+        .collect()
+}
+
+#[cfg(not(feature = "embedded-data"))]
+pub fn languages() -> Vec<String> {
+    Vec::new()
+}
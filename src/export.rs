@@ -0,0 +1,83 @@
+use std::{
+    fs::{self, File},
+    io::Write,
+    path::Path,
+};
+
+use serde::Serialize;
+
+use crate::{app::Flags, loading::read_lines, paths::data_file};
+
+/// Writes the armor and decorations of `data` to `dir` in the layout of the data files,
+/// so the export can be used as a data pack. Returns the number of rows written.
+pub fn export_data(data: &Flags, dir: &Path) -> csv::Result<usize> {
+    fs::create_dir_all(dir)?;
+    let armor = [
+        ("head.txt", &data.head),
+        ("body.txt", &data.body),
+        ("arms.txt", &data.arms),
+        ("waist.txt", &data.waist),
+        ("legs.txt", &data.legs),
+    ];
+    let mut count = 0;
+    for (name, pieces) in armor {
+        write_table(&dir.join(name), &header(name), pieces)?;
+        count += pieces.len();
+    }
+    write_table(
+        &dir.join("decorations.txt"),
+        &header("decorations.txt"),
+        &data.decorations,
+    )?;
+    Ok(count + data.decorations.len())
+}
+
+/// The first line of a data file, it names the columns and is skipped when loading
+fn header(name: &str) -> String {
+    read_lines(&data_file(name))
+        .ok()
+        .and_then(|lines| lines.into_iter().next())
+        .filter(|line| line.starts_with('#'))
+        .unwrap_or_else(|| "#".to_owned())
+}
+
+fn write_table<T: Serialize>(path: &Path, header: &str, rows: &[T]) -> csv::Result<()> {
+    let mut file = File::create(path)?;
+    writeln!(file, "{header}")?;
+    let mut writer = csv::WriterBuilder::new()
+        .has_headers(false)
+        .from_writer(file);
+    for row in rows {
+        writer.serialize(row)?;
+    }
+    Ok(writer.flush()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::{decorations::Decoration, loading::read_required};
+
+    #[test]
+    fn exported_decorations_load_again() {
+        let decorations: Vec<Decoration> = [
+            json!({"name": "Attack Jewel 1", "slots": 1, "skill_1_name": "Attack",
+                "skill_1_points": 1, "skill_2_name": "Defense", "skill_2_points": -1,
+                "material_a1_name": "Ore", "material_a1_count": 2}),
+            json!({"name": "Guard Jewel 3", "slots": 3, "skill_1_name": "Guard",
+                "skill_1_points": 5, "material_b1_name": "Scale", "material_b1_count": 1}),
+        ]
+        .into_iter()
+        .map(|row| serde_json::from_value(row).unwrap())
+        .collect();
+        let path = std::env::temp_dir().join(format!("asf-export-{}.txt", std::process::id()));
+        write_table(&path, "#name,rarity,slots", &decorations).unwrap();
+        let mut errors = Vec::new();
+        let loaded: Vec<Decoration> = read_required(&path, 1, &mut errors).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert!(errors.is_empty());
+        assert_eq!(loaded, decorations);
+    }
+}
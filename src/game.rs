@@ -0,0 +1,99 @@
+use std::io;
+
+use serde::Deserialize;
+
+use crate::{
+    loading::LoadError,
+    paths::{data_file, read_string},
+};
+
+/// The decoration search only knows decorations with up to 3 slots
+pub const MAX_SUPPORTED_SLOTS: i32 = 3;
+
+/// Describes the game of a data pack, read from the game.json next to its data files
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default)]
+pub struct Game {
+    pub name: String,
+    /// Names of the hunter ranks,
+    /// the `hunter_rank` of armor and decorations is a 1 based index into them
+    pub hunter_ranks: Vec<String>,
+    pub village_ranks: i32,
+    /// Most slots a weapon, armor piece or charm can have
+    pub max_slots: i32,
+    /// Most skill points a charm can have in a single skill
+    pub max_charm_points: i32,
+    /// Whether armor pieces with torso up copy the skills of the body piece
+    pub torso_up: bool,
+}
+
+impl Default for Game {
+    /// Monster Hunter 4 Ultimate, the game of data packs without a game.json
+    fn default() -> Self {
+        Game {
+            name: "Monster Hunter 4 Ultimate".to_owned(),
+            hunter_ranks: [
+                "HR1", "HR2", "HR3", "HR4", "HR5", "HR6", "HR7", "HR8", "G1", "G2", "G3",
+            ]
+            .map(str::to_owned)
+            .to_vec(),
+            village_ranks: 10,
+            max_slots: 3,
+            max_charm_points: 14,
+            torso_up: true,
+        }
+    }
+}
+
+impl Game {
+    pub fn load() -> Result<Game, LoadError> {
+        let path = data_file("game.json");
+        let manifest_error = |message: String| LoadError::Manifest {
+            path: path.clone(),
+            message,
+        };
+        let game: Game = match read_string(&path) {
+            Ok(text) => {
+                serde_json::from_str(&text).map_err(|error| manifest_error(error.to_string()))?
+            }
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Game::default(),
+            Err(error) => return Err(manifest_error(error.to_string())),
+        };
+        if !(0..=MAX_SUPPORTED_SLOTS).contains(&game.max_slots) {
+            return Err(manifest_error(format!(
+                "max_slots has to be between 0 and {MAX_SUPPORTED_SLOTS}"
+            )));
+        }
+        Ok(game)
+    }
+
+    /// Rank that includes every armor piece
+    pub fn all_ranks(&self) -> i32 {
+        self.hunter_ranks.len() as i32 + 1
+    }
+
+    /// Entries of the hunter rank picker, `all` is the label of the last one
+    pub fn hunter_rank_options(&self, all: &str) -> Vec<HunterRank> {
+        self.hunter_ranks
+            .iter()
+            .chain([&all.to_owned()])
+            .enumerate()
+            .map(|(index, label)| HunterRank {
+                rank: index as i32 + 1,
+                label: label.clone(),
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HunterRank {
+    pub rank: i32,
+    pub label: String,
+}
+
+impl std::fmt::Display for HunterRank {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.label)
+    }
+}
@@ -2,11 +2,21 @@
 pub mod segmented_button;
 pub mod armor;
 pub mod component;
+pub mod game;
 pub mod types;
 pub mod algorithm;
 pub mod app;
 pub mod skill;
+pub mod skill_id;
 pub mod skillpoint;
+pub mod strings;
 pub mod charms;
+pub mod charm_list;
+pub mod loading;
+pub mod paths;
+pub mod relics;
 pub mod requirements;
 pub mod decorations;
+pub mod embedded;
+pub mod export;
+pub mod validation;
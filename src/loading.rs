@@ -0,0 +1,276 @@
+use std::{
+    fmt::Display,
+    io,
+    path::{Path, PathBuf},
+};
+
+use csv::StringRecord;
+use serde::de::DeserializeOwned;
+
+use crate::paths::{read_file, read_string};
+
+#[derive(Debug)]
+pub enum LoadError {
+    /// The file is missing or can't be read
+    Io { path: PathBuf, error: io::Error },
+    /// A row of a csv file that doesn't fit the expected format
+    Row {
+        path: PathBuf,
+        line: Option<u64>,
+        field: Option<u64>,
+        message: String,
+    },
+    /// A translation file whose lines don't match the entries to translate
+    Lines {
+        path: PathBuf,
+        expected: usize,
+        found: usize,
+    },
+    /// A game.json that can't be read or describes a game asf can't handle
+    Manifest { path: PathBuf, message: String },
+    /// A skill attribute of an armor piece or decoration that isn't in skills.txt
+    UnknownSkill {
+        path: PathBuf,
+        part: String,
+        skill: String,
+    },
+    /// A translated skill attribute that is used for more than one attribute
+    DuplicateSkill { path: PathBuf, skill: String },
+}
+
+impl Display for LoadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LoadError::Io { path, error } => write!(f, "{}: {error}", path.display()),
+            LoadError::Row {
+                path,
+                line,
+                field,
+                message,
+            } => {
+                write!(f, "{}", path.display())?;
+                if let Some(line) = line {
+                    write!(f, " line {line}")?;
+                }
+                if let Some(field) = field {
+                    write!(f, " field {}", field + 1)?;
+                }
+                write!(f, ": {message}")
+            }
+            LoadError::Lines {
+                path,
+                expected,
+                found,
+            } => write!(f, "{}: {found} lines, expected {expected}", path.display()),
+            LoadError::Manifest { path, message } => write!(f, "{}: {message}", path.display()),
+            LoadError::UnknownSkill { path, part, skill } => {
+                write!(
+                    f,
+                    "{}: {part} has the unknown skill {skill}",
+                    path.display()
+                )
+            }
+            LoadError::DuplicateSkill { path, skill } => {
+                write!(
+                    f,
+                    "{}: the skill {skill} is there more than once",
+                    path.display()
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for LoadError {}
+
+impl LoadError {
+    fn io(path: &Path, error: io::Error) -> Self {
+        LoadError::Io {
+            path: path.to_path_buf(),
+            error,
+        }
+    }
+
+    fn row(path: &Path, error: csv::Error) -> Self {
+        let line = error.position().map(|position| position.line());
+        let (field, message) = match error.kind() {
+            csv::ErrorKind::Deserialize { err, .. } => (err.field(), err.kind().to_string()),
+            _ => (None, error.to_string()),
+        };
+        LoadError::Row {
+            path: path.to_path_buf(),
+            line,
+            field,
+            message,
+        }
+    }
+
+    pub fn is_not_found(&self) -> bool {
+        matches!(self, LoadError::Io { error, .. } if error.kind() == io::ErrorKind::NotFound)
+    }
+}
+
+/// Reads the rows of a headerless csv file after skipping `skip` rows.
+/// Rows for which `ignore` returns true are left out,
+/// rows that can't be read are returned as errors.
+pub fn read_csv<T: DeserializeOwned>(
+    path: &Path,
+    skip: usize,
+    ignore: impl Fn(&StringRecord) -> bool,
+) -> Result<(Vec<T>, Vec<LoadError>), LoadError> {
+    let contents = read_file(path).map_err(|error| LoadError::io(path, error))?;
+    Ok(parse_csv(&contents, path, skip, ignore))
+}
+
+/// `read_csv` of contents that were already read, `path` is only used for the errors
+fn parse_csv<T: DeserializeOwned>(
+    contents: &[u8],
+    path: &Path,
+    skip: usize,
+    ignore: impl Fn(&StringRecord) -> bool,
+) -> (Vec<T>, Vec<LoadError>) {
+    let mut reader = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .from_reader(contents);
+    let mut rows = Vec::new();
+    let mut errors = Vec::new();
+    for record in reader.records().skip(skip) {
+        match record.and_then(|record| {
+            if ignore(&record) {
+                Ok(None)
+            } else {
+                record.deserialize(None).map(Some)
+            }
+        }) {
+            Ok(Some(row)) => rows.push(row),
+            Ok(None) => (),
+            Err(error) => errors.push(LoadError::row(path, error)),
+        }
+    }
+    (rows, errors)
+}
+
+/// Reads a file asf can't work without, rows that can't be read are added to `errors`
+pub fn read_required<T: DeserializeOwned>(
+    path: &Path,
+    skip: usize,
+    errors: &mut Vec<LoadError>,
+) -> Result<Vec<T>, LoadError> {
+    let (rows, row_errors) = read_csv(path, skip, |_| false)?;
+    errors.extend(row_errors);
+    Ok(rows)
+}
+
+/// Reads a file that doesn't have to exist, like the charm list of a new user
+pub fn read_optional<T: DeserializeOwned>(
+    path: &Path,
+    skip: usize,
+    errors: &mut Vec<LoadError>,
+) -> Vec<T> {
+    match read_csv(path, skip, |_| false) {
+        Ok((rows, row_errors)) => {
+            errors.extend(row_errors);
+            rows
+        }
+        Err(error) => {
+            if !error.is_not_found() {
+                errors.push(error);
+            }
+            Vec::new()
+        }
+    }
+}
+
+/// Lines of a text file without the byte order mark
+pub fn read_lines(path: &Path) -> Result<Vec<String>, LoadError> {
+    let text = read_string(path).map_err(|error| LoadError::io(path, error))?;
+    Ok(text
+        .trim_start_matches('\u{feff}')
+        .lines()
+        .map(str::to_owned)
+        .collect())
+}
+
+/// Like `read_lines` but fails unless there are exactly `expected` lines
+pub fn read_translation(path: &Path, expected: usize) -> Result<Vec<String>, LoadError> {
+    let lines = read_lines(path)?;
+    if lines.len() != expected {
+        return Err(LoadError::Lines {
+            path: path.to_path_buf(),
+            expected,
+            found: lines.len(),
+        });
+    }
+    Ok(lines)
+}
+
+/// Reads the translated skill attributes and skill names of a skills.txt.
+/// The attributes come first, followed by an empty line and the skills.
+pub fn read_skill_translation(
+    path: &Path,
+    attributes: usize,
+    skills: usize,
+) -> Result<(Vec<String>, Vec<String>), LoadError> {
+    let mut lines = read_lines(path)?;
+    let seperator = lines
+        .iter()
+        .position(|line| line.is_empty())
+        .unwrap_or(lines.len());
+    if seperator != attributes || lines.len() != attributes + 1 + skills {
+        return Err(LoadError::Lines {
+            path: path.to_path_buf(),
+            expected: attributes + 1 + skills,
+            found: lines.len(),
+        });
+    }
+    let skill_names = lines.split_off(seperator + 1);
+    lines.pop();
+    Ok((lines, skill_names))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rows_that_cant_be_read_are_reported_with_line_and_field() {
+        let path = Path::new("armor.txt");
+        let contents = "#name,points\nHelm,1\nMail,x\nGloves,3\n";
+        let (rows, errors) = parse_csv::<(String, i32)>(contents.as_bytes(), path, 1, |_| false);
+        assert_eq!(rows, [("Helm".to_owned(), 1), ("Gloves".to_owned(), 3)]);
+        assert!(matches!(
+            &errors[..],
+            [LoadError::Row {
+                line: Some(3),
+                field: Some(1),
+                ..
+            }]
+        ));
+        assert!(errors[0]
+            .to_string()
+            .starts_with("armor.txt line 3 field 2: "));
+    }
+
+    #[test]
+    fn ignored_rows_are_not_errors() {
+        let contents = "Helm,1\n,\nGloves,3\n";
+        let (rows, errors) =
+            parse_csv::<(String, i32)>(contents.as_bytes(), Path::new("armor.txt"), 0, |record| {
+                record.iter().all(str::is_empty)
+            });
+        assert_eq!(rows.len(), 2);
+        assert!(errors.is_empty());
+    }
+
+    #[test]
+    fn only_required_files_have_to_exist() {
+        let path = std::env::temp_dir().join("asf-missing-file.txt");
+        let mut errors = Vec::new();
+        assert!(read_optional::<(String, i32)>(&path, 0, &mut errors).is_empty());
+        assert!(errors.is_empty());
+        let error = read_required::<(String, i32)>(&path, 0, &mut errors).unwrap_err();
+        assert!(error.is_not_found());
+        assert!(error.to_string().starts_with(&path.display().to_string()));
+    }
+}
@@ -1,75 +1,73 @@
-#![feature(iter_collect_into)]
-
 use asf::{
     app::{subscription, update, view, Flags, State},
-    skill::Skill,
+    export::export_data,
+    paths::{migrate_user_files, select_game, GAME_VARIABLE},
+    validation::{validate, validate_all},
 };
 use iced::{window, Task, Theme};
-use itertools::Itertools;
-use serde::de::DeserializeOwned;
 use std::error::Error;
-fn read_to_vec<T>(path: &str, skip: usize) -> Vec<T>
-where
-    T: DeserializeOwned,
-{
-    csv::ReaderBuilder::new()
-        .has_headers(false)
-        .from_path(path)
-        .map(|mut x| x.deserialize().skip(skip).flatten().collect())
-        .unwrap_or_default()
-}
 
 fn main() -> Result<(), Box<dyn Error>> {
-    let skills: Vec<Skill> = csv::ReaderBuilder::new()
-        .flexible(true)
-        .has_headers(false)
-        .from_path("./skills.txt")
-        .unwrap()
-        .deserialize()
-        .skip(1)
-        .flatten()
-        .collect();
-    let body = read_to_vec("./body.txt", 1);
-    let head = read_to_vec("./head.txt", 1);
-    let arms = read_to_vec("./arms.txt", 1);
-    let waist = read_to_vec("./waist.txt", 1);
-    let legs = read_to_vec("./legs.txt", 1);
-    let relic_body = read_to_vec("./relic_body.txt", 0);
-    let relic_head = read_to_vec("./relic_head.txt", 0);
-    let relic_arms = read_to_vec("./relic_arms.txt", 0);
-    let relic_waist = read_to_vec("./relic_waist.txt", 0);
-    let relic_legs = read_to_vec("./relic_legs.txt", 0);
-    let charms = read_to_vec("./mycharms.txt", 1);
-    let decorations = read_to_vec("./decorations.txt", 1);
-    let components = csv::ReaderBuilder::new()
-        .flexible(true)
-        .has_headers(false)
-        .from_path("./components.txt")
-        .unwrap()
-        .deserialize()
-        .flatten()
-        .collect_vec();
-    let data = Flags {
-        body,
-        head,
-        arms,
-        waist,
-        legs,
-        relic_body,
-        relic_head,
-        relic_arms,
-        relic_legs,
-        relic_waist,
-        charms,
-        decorations,
-        skills,
-        components,
+    //`--game <name>` loads the data pack in games/<name> instead of the default one
+    let args = std::env::args().collect::<Vec<_>>();
+    let game = args
+        .iter()
+        .position(|arg| arg == "--game")
+        .and_then(|index| args.get(index + 1).cloned())
+        .or_else(|| std::env::var(GAME_VARIABLE).ok());
+    if let Some(game) = game {
+        select_game(&game);
+    }
+    let validating = args.iter().any(|arg| arg == "--validate");
+    //`--export <folder>` writes the armor and decorations as data files instead of starting the GUI
+    let export = args
+        .iter()
+        .position(|arg| arg == "--export")
+        .and_then(|index| args.get(index + 1).cloned());
+    if !validating && export.is_none() {
+        migrate_user_files()?;
+    }
+    let (data, mut load_errors) = match Flags::load() {
+        Ok(loaded) => loaded,
+        Err(error) => {
+            eprintln!("asf can't start without its data files: {error}");
+            std::process::exit(1);
+        }
     };
+    //check the data files and translations without starting the GUI
+    if validating {
+        load_errors.extend(validate_all(&data));
+        for error in &load_errors {
+            println!("{error}");
+        }
+        println!("{} problems found", load_errors.len());
+        std::process::exit(if load_errors.is_empty() { 0 } else { 1 });
+    }
+    if let Some(dir) = export {
+        match export_data(&data, dir.as_ref()) {
+            Ok(count) => println!("{count} rows written to {dir}"),
+            Err(error) => {
+                eprintln!("The data could not be exported: {error}");
+                std::process::exit(1);
+            }
+        }
+        return Ok(());
+    }
+    load_errors.extend(validate(&data));
+    for error in &load_errors {
+        eprintln!("{error}");
+    }
     let window = window::Settings {
         exit_on_close_request: false,
         ..window::Settings::default()
     };
-    let state = State::new(data);
+    let state = match State::new(data, load_errors) {
+        Ok(state) => state,
+        Err(error) => {
+            eprintln!("asf can't start without its translations: {error}");
+            std::process::exit(1);
+        }
+    };
     iced::application("Armor Set Finder", update, view)
         .theme(|_| Theme::SolarizedLight)
         .subscription(subscription)
@@ -0,0 +1,149 @@
+use std::{
+    env, fs, io,
+    path::{Path, PathBuf},
+    sync::OnceLock,
+};
+
+use itertools::Itertools;
+
+use crate::{embedded, types::Language};
+
+/// Overrides the directory of skills.txt, the armor files and the Languages folder
+pub const DATA_DIR_VARIABLE: &str = "ASF_DATA_DIR";
+/// Overrides the directory of the settings, charms and relics
+pub const USER_DIR_VARIABLE: &str = "ASF_USER_DIR";
+/// Selects a data pack in the games folder like `--game`
+pub const GAME_VARIABLE: &str = "ASF_GAME";
+
+static GAME: OnceLock<String> = OnceLock::new();
+
+/// Selects the data pack in `games/<name>` of the data directory
+/// instead of the one in the data directory itself.
+/// Has to be called before any file is read, returns false otherwise.
+pub fn select_game(name: &str) -> bool {
+    GAME.set(name.to_owned()).is_ok() && DATA_DIR.get().is_none() && USER_DIR.get().is_none()
+}
+
+static DATA_DIR: OnceLock<PathBuf> = OnceLock::new();
+static USER_DIR: OnceLock<PathBuf> = OnceLock::new();
+
+/// Files written by asf, older versions kept them next to the executable
+const USER_FILES: &[&str] = &[
+    "settings.json",
+    "settings",
+    "mycharms.txt",
+    "relic_head.txt",
+    "relic_body.txt",
+    "relic_arms.txt",
+    "relic_waist.txt",
+    "relic_legs.txt",
+];
+
+/// The data files are looked for next to the executable and then in the working directory
+pub fn data_dir() -> &'static Path {
+    DATA_DIR.get_or_init(|| {
+        let dir = env::var_os(DATA_DIR_VARIABLE)
+            .map(PathBuf::from)
+            .or_else(|| executable_dir().filter(|dir| dir.join("skills.txt").is_file()))
+            .unwrap_or_else(|| PathBuf::from("."));
+        match GAME.get() {
+            Some(game) => dir.join("games").join(game),
+            None => dir,
+        }
+    })
+}
+
+/// The asf folder in the config directory of the user (`~/.config/asf`, `%APPDATA%\asf`, ...).
+/// Other games than the default one get a sub folder.
+pub fn user_dir() -> &'static Path {
+    USER_DIR.get_or_init(|| {
+        let dir = env::var_os(USER_DIR_VARIABLE)
+            .map(PathBuf::from)
+            .or_else(|| dirs::config_dir().map(|dir| dir.join("asf")))
+            .unwrap_or_else(|| data_dir().to_path_buf());
+        match GAME.get() {
+            Some(game) => dir.join(game),
+            None => dir,
+        }
+    })
+}
+
+fn executable_dir() -> Option<PathBuf> {
+    env::current_exe()
+        .ok()
+        .and_then(|exe| exe.parent().map(Path::to_path_buf))
+}
+
+pub fn data_file(name: &str) -> PathBuf {
+    data_dir().join(name)
+}
+
+pub fn user_file(name: &str) -> PathBuf {
+    user_dir().join(name)
+}
+
+pub fn language_file(language: Language, name: &str) -> PathBuf {
+    data_dir()
+        .join("Languages")
+        .join(language.directory())
+        .join(name)
+}
+
+/// Reads a file, data files that don't exist are taken from the binary if it was built with them.
+/// Data packs of other games are never replaced by the embedded files.
+pub fn read_file(path: &Path) -> io::Result<Vec<u8>> {
+    match fs::read(path) {
+        Err(error) if error.kind() == io::ErrorKind::NotFound => GAME
+            .get()
+            .is_none()
+            .then(|| path.strip_prefix(data_dir()).ok())
+            .flatten()
+            .and_then(embedded::file)
+            .map(<[u8]>::to_vec)
+            .ok_or(error),
+        result => result,
+    }
+}
+
+pub fn read_string(path: &Path) -> io::Result<String> {
+    String::from_utf8(read_file(path)?)
+        .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))
+}
+
+/// The folders in Languages, including the embedded ones
+pub fn language_dirs() -> Vec<PathBuf> {
+    let languages = data_dir().join("Languages");
+    let embedded = match GAME.get() {
+        Some(_) => Vec::new(),
+        None => embedded::languages(),
+    };
+    fs::read_dir(&languages)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_dir())
+        .chain(embedded.into_iter().map(|name| languages.join(name)))
+        .sorted()
+        .dedup()
+        .collect()
+}
+
+/// Copies the user files of older versions from the directory of the executable
+/// to the user directory.
+/// The old files are left in place, files that already exist in the user directory are left alone.
+/// Older versions didn't know other games, so there is nothing to copy for them.
+pub fn migrate_user_files() -> std::io::Result<()> {
+    fs::create_dir_all(user_dir())?;
+    let Some(source_dir) = executable_dir().filter(|_| GAME.get().is_none()) else {
+        return Ok(());
+    };
+    for name in USER_FILES {
+        let source = source_dir.join(name);
+        let target = user_file(name);
+        if source.is_file() && !target.exists() {
+            fs::copy(&source, &target)?;
+        }
+    }
+    Ok(())
+}
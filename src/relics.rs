@@ -0,0 +1,145 @@
+use std::ops::RangeInclusive;
+
+use itertools::Itertools;
+
+use crate::{
+    armor::Armor,
+    skill::Skill,
+    skill_id::SkillIds,
+    skillpoint::{intern_skills, SkillPoints},
+};
+
+/// Defence the relic editor allows, base and upgraded
+pub const RELIC_DEFENCE: RangeInclusive<i32> = 0..=999;
+/// Resistances the relic editor allows
+pub const RELIC_RESISTANCE: RangeInclusive<i32> = -30..=30;
+/// Relic pieces are marked with this in front of their name
+pub const RELIC_PREFIX: &str = "🪙 ";
+
+/// Every relic armor piece that can drop:
+/// one skill with up to its relic cap and any number of slots.
+/// Pieces with only slots are left out, searches only use pieces with a required skill.
+/// Relic pieces can be of any armor type, so the same pieces are used for every slot.
+/// Their defence is unknown, so they get the whole defence range and the lowest resistances.
+pub fn possible_relics(skills: &[Skill], ids: &SkillIds, max_slots: i32) -> Vec<Armor> {
+    let resistance = *RELIC_RESISTANCE.start();
+    let relic = |name: &str, slots: i32| Armor {
+        name: RELIC_PREFIX.to_owned()
+            + &[name, &"○".repeat(slots as usize)]
+                .into_iter()
+                .filter(|part| !part.is_empty())
+                .join(" "),
+        slots,
+        rarity: 10,
+        defence_min: *RELIC_DEFENCE.start(),
+        defence_max: *RELIC_DEFENCE.end(),
+        defence_fire: resistance,
+        defence_water: resistance,
+        defence_thunder: resistance,
+        defence_ice: resistance,
+        defence_dragon: resistance,
+        ..Default::default()
+    };
+    let mut relics = Vec::new();
+    for attribute in skills.iter().map(|skill| &skill.name_attribute).unique() {
+        let Some(max_points) = Skill::max_relic_armor_points_of(skills, attribute) else {
+            continue;
+        };
+        relics.extend(
+            (1..=max_points)
+                .cartesian_product(0..=max_slots)
+                .map(|(points, slots)| Armor {
+                    skills: vec![SkillPoints::new(attribute, points)],
+                    ..relic(&format!("{attribute} +{points}"), slots)
+                }),
+        );
+    }
+    intern_skills(&mut relics, ids);
+    relics
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::{algorithm::trim, requirements::Requirement, skillpoint::SkillPoint};
+
+    fn skills() -> Vec<Skill> {
+        [
+            json!({"name": "Attack Up (S)", "name_attribute": "Attack", "points": 10,
+                "max_weapon_skill_points": 4, "max_armor_skill_points": 2}),
+            json!({"name": "Attack Up (M)", "name_attribute": "Attack", "points": 15,
+                "max_weapon_skill_points": 4, "max_armor_skill_points": 3}),
+            json!({"name": "Guard +1", "name_attribute": "Guard", "points": 10,
+                "max_weapon_skill_points": 5}),
+            json!({"name": "Sharpness +1", "name_attribute": "Handicraft", "points": 10}),
+        ]
+        .into_iter()
+        .map(|skill| serde_json::from_value(skill).unwrap())
+        .collect()
+    }
+
+    #[test]
+    fn every_point_and_slot_count_up_to_the_cap() {
+        let skills = skills();
+        let ids = SkillIds::new(&skills);
+        let relics = possible_relics(&skills, &ids, 2);
+        let combinations = relics
+            .iter()
+            .map(|relic| {
+                (
+                    relic.skills[0].name.as_str(),
+                    relic.skills[0].points,
+                    relic.slots,
+                )
+            })
+            .collect_vec();
+        let expected = (1..=3)
+            .cartesian_product(0..=2)
+            .map(|(points, slots)| ("Attack", points, slots))
+            .chain(
+                (1..=5)
+                    .cartesian_product(0..=2)
+                    .map(|(points, slots)| ("Guard", points, slots)),
+            )
+            .collect_vec();
+        assert_eq!(combinations, expected);
+        assert!(relics
+            .iter()
+            .all(|relic| relic.name.starts_with(RELIC_PREFIX)));
+        assert_eq!(relics[4].name, format!("{RELIC_PREFIX}Attack +2 ○"));
+        assert_eq!(relics[4].points(ids.get("Attack")), 2);
+    }
+
+    #[test]
+    fn no_pieces_with_only_slots() {
+        let skills = skills();
+        let relics = possible_relics(&skills, &SkillIds::new(&skills), 3);
+        assert!(relics.iter().all(|relic| relic.skills.len() == 1));
+    }
+
+    #[test]
+    fn real_pieces_beat_relics_with_the_same_skills_and_slots() {
+        let skills = skills();
+        let ids = SkillIds::new(&skills);
+        let mut real = Armor {
+            name: "Rathalos Helm".to_owned(),
+            slots: 1,
+            defence_min: 1,
+            skills: vec![SkillPoints::new("Attack", 2)],
+            ..Default::default()
+        };
+        real.intern_skills(&ids);
+        let parts = [vec![real], possible_relics(&skills, &ids, 1)].concat();
+        let requirements = [Requirement {
+            name: "Attack".to_owned(),
+            points: 10,
+            id: ids.get("Attack"),
+        }];
+        let (_, trimmed) = trim(&parts, |_| true, &requirements, &[1.0], 10);
+        let names = trimmed.iter().map(|part| part.name.as_str()).collect_vec();
+        assert!(names.contains(&"Rathalos Helm"));
+        assert!(!names.contains(&format!("{RELIC_PREFIX}Attack +2 ○").as_str()));
+    }
+}
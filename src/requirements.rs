@@ -1,7 +1,12 @@
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Eq)]
+use crate::skill_id::SkillId;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Eq, Default)]
 pub struct Requirement {
     pub name: String,
     pub points: i32,
+    /// Id of `name`, only set for the requirements of a search
+    #[serde(skip)]
+    pub id: SkillId,
 }
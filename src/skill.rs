@@ -11,7 +11,10 @@ pub struct Skill {
     pub category: Option<String>,
     pub category_2: Option<String>,
     pub max_weapon_skill_points: Option<i32>,
-    pub max_armor_skill_points: Option<i32>
+    pub max_armor_skill_points: Option<i32>,
+    /// Loaded from skill_descriptions.txt of the selected language
+    #[serde(skip)]
+    pub description: Option<String>,
 }
 
 impl Skill {
@@ -20,7 +23,8 @@ impl Skill {
             .iter()
             .cloned()
             .into_group_map_by(|skill| skill.name_attribute.clone());
-        let mut skill_types = vec!["All".to_owned()];
+        //the first three are the special categories of tags.txt
+        let mut skill_types = ["All", "Misc", "Related"].map(str::to_owned).to_vec();
         skill_types.extend(
             skills
                 .iter()
@@ -34,6 +38,39 @@ impl Skill {
     pub fn is_relic_skill(&self) -> bool {
         self.max_weapon_skill_points.is_some()
     }
+    /// Most points relic armor can have of this skill,
+    /// skills without an armor cap use the weapon cap
+    pub fn max_relic_armor_points(&self) -> Option<i32> {
+        self.max_armor_skill_points.or(self.max_weapon_skill_points)
+    }
+    /// Relic armor cap of an attribute, the highest one of the skills sharing it
+    pub fn max_relic_armor_points_of(skills: &[Self], attribute: &str) -> Option<i32> {
+        skills
+            .iter()
+            .filter(|skill| skill.name_attribute == attribute)
+            .filter_map(Skill::max_relic_armor_points)
+            .max()
+    }
+    pub fn is_available_for(&self, weapon_type: i32) -> bool {
+        self.weapon_type == 0 || weapon_type == 0 || self.weapon_type == weapon_type
+    }
+    /// Returns the skill of a group sharing one attribute that is activated by `points`
+    pub fn activated(group: &[Self], points: i32, weapon_type: i32) -> Option<&Self> {
+        let available = group
+            .iter()
+            .filter(|skill| skill.is_available_for(weapon_type));
+        if points > 0 {
+            available
+                .filter(|skill| skill.points > 0 && skill.points <= points)
+                .max_by_key(|skill| skill.points)
+        } else if points < 0 {
+            available
+                .filter(|skill| skill.points < 0 && skill.points >= points)
+                .min_by_key(|skill| skill.points)
+        } else {
+            None
+        }
+    }
     pub fn has_category(&self, category: Option<&str>) -> bool {
         if category == Some("All") || category == self.category.as_deref() {
             true
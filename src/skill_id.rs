@@ -0,0 +1,106 @@
+use std::collections::HashMap;
+
+use itertools::Itertools;
+
+use crate::{skill::Skill, skillpoint::SkillPoints};
+
+/// Index of a skill attribute in skills.txt, the same in every language
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct SkillId(u16);
+
+impl SkillId {
+    /// Id of names that aren't in skills.txt, no part has points in it
+    pub const UNKNOWN: SkillId = SkillId(u16::MAX);
+}
+
+impl Default for SkillId {
+    fn default() -> Self {
+        SkillId::UNKNOWN
+    }
+}
+
+/// Points of a part in every skill attribute, indexed by `SkillId` so that looking them up is cheap
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct SkillPointsById(Vec<i32>);
+
+impl SkillPointsById {
+    /// Unknown ids are left out
+    pub fn new(skills: &[(SkillId, i32)]) -> Self {
+        let mut points = Vec::new();
+        for &(id, skill_points) in skills.iter().filter(|(id, _)| *id != SkillId::UNKNOWN) {
+            let index = id.0 as usize;
+            if points.len() <= index {
+                points.resize(index + 1, 0);
+            }
+            points[index] += skill_points;
+        }
+        SkillPointsById(points)
+    }
+    pub fn get(&self, id: SkillId) -> i32 {
+        self.0.get(id.0 as usize).copied().unwrap_or(0)
+    }
+}
+
+/// Maps the skill attribute names of one language to their ids
+#[derive(Debug, Clone, Default)]
+pub struct SkillIds {
+    ids: HashMap<String, SkillId>,
+}
+
+impl SkillIds {
+    pub fn new(skills: &[Skill]) -> Self {
+        let ids = skills
+            .iter()
+            .map(|skill| skill.name_attribute.clone())
+            .unique()
+            .enumerate()
+            .map(|(index, attribute)| (attribute, SkillId(index as u16)))
+            .collect();
+        SkillIds { ids }
+    }
+    /// The ids of the translated attributes, the same as the ones of the base attributes
+    pub fn translate(&self, translation: &HashMap<String, String>) -> Self {
+        let ids = self
+            .ids
+            .iter()
+            .filter_map(|(attribute, &id)| Some((translation.get(attribute)?.clone(), id)))
+            .collect();
+        SkillIds { ids }
+    }
+    pub fn get(&self, attribute: &str) -> SkillId {
+        self.ids.get(attribute).copied().unwrap_or_default()
+    }
+    /// The skill points of a part, names that aren't skills (like torso up) are left out
+    pub fn intern(&self, skills: &[SkillPoints]) -> SkillPointsById {
+        SkillPointsById::new(
+            &skills
+                .iter()
+                .filter(|skill| !skill.name.is_empty() && skill.points != 0)
+                .map(|skill| (self.get(&skill.name), skill.points))
+                .collect_vec(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn translated_ids_are_the_base_ids() {
+        let skills = ["A", "B", "C"].map(|attribute| {
+            serde_json::from_value::<Skill>(serde_json::json!({ "name_attribute": attribute }))
+                .unwrap()
+        });
+        let ids = SkillIds::new(&skills);
+        //two attributes with the same translation don't move the ids of the others
+        let translation = [("A", "X"), ("B", "X"), ("C", "Z")]
+            .into_iter()
+            .map(|(attribute, translated)| (attribute.to_owned(), translated.to_owned()))
+            .collect();
+        let translated = ids.translate(&translation);
+        assert_eq!(translated.get("Z"), ids.get("C"));
+        assert!([ids.get("A"), ids.get("B")].contains(&translated.get("X")));
+        assert_eq!(translated.get("A"), SkillId::UNKNOWN);
+    }
+}
@@ -1,10 +1,69 @@
 use std::collections::HashMap;
 
+use serde::{Deserialize, Serialize};
+
+use crate::skill_id::{SkillId, SkillIds, SkillPointsById};
+
+/// Points of a part in one skill attribute
+#[derive(Serialize, Deserialize, Debug, PartialEq, Eq, Hash, Clone, Default)]
+pub struct SkillPoints {
+    pub name: String,
+    pub points: i32,
+}
+
+impl SkillPoints {
+    pub fn new(name: impl Into<String>, points: i32) -> Self {
+        SkillPoints {
+            name: name.into(),
+            points,
+        }
+    }
+    /// Reads the name and points columns of a csv row, empty columns are left out
+    pub fn from_columns<const N: usize>(columns: [(String, Option<i32>); N]) -> Vec<Self> {
+        columns
+            .into_iter()
+            .filter(|(name, _)| !name.is_empty())
+            .map(|(name, points)| SkillPoints::new(name, points.unwrap_or(0)))
+            .collect()
+    }
+    /// Fills the name and points columns of a csv row, the skills past `N` are dropped
+    pub fn to_columns<const N: usize>(skills: Vec<Self>) -> [(String, Option<i32>); N] {
+        let mut skills = skills.into_iter();
+        std::array::from_fn(|_| {
+            skills
+                .next()
+                .map(|skill| (skill.name, Some(skill.points)))
+                .unwrap_or_default()
+        })
+    }
+    pub fn translate(skills: &mut [Self], translation: &HashMap<String, String>) {
+        for skill in skills {
+            if let Some(t) = translation.get(&skill.name) {
+                skill.name.clone_from(t);
+            }
+        }
+    }
+}
 
 pub trait SkillPoint {
-    fn points(&self, ability: &str) -> i32;
+    /// Skill points of the part, filled by `intern_skills`
+    fn points_by_id(&self) -> &SkillPointsById;
+    fn points(&self, skill: SkillId) -> i32 {
+        self.points_by_id().get(skill)
+    }
     fn max_defence(&self) -> u32;
     fn defence(&self) -> u32;
     fn slots(&self) -> i32;
+    /// Looks up the ids of the skill names, has to be called after loading or creating a part.
+    /// Translating keeps the ids, they are the same in every language.
+    fn intern_skills(&mut self, ids: &SkillIds);
     fn translate_skills(&mut self, translation: &HashMap<String, String>);
+    /// Skill attributes this part has points in
+    fn skill_names(&self) -> Vec<&str>;
+}
+
+pub fn intern_skills<T: SkillPoint>(parts: &mut [T], ids: &SkillIds) {
+    for part in parts {
+        part.intern_skills(ids);
+    }
 }
@@ -0,0 +1,95 @@
+use std::{collections::HashMap, fmt::Display, path::Path};
+
+use crate::{
+    paths::{language_file, read_string},
+    types::Language,
+};
+
+/// Labels of asf that have a differently worded counterpart in the string table of Athena's ASS
+const ALIASES: &[(&str, &str)] = &[
+    ("Gathering Hall", "Guild Quests"),
+    ("Village Rank", "Caravan Quests"),
+    ("Melee", "Blademaster"),
+    ("Marksman", "Gunner"),
+    ("Chest", "Body"),
+    ("Abort", "Cancel"),
+    ("Add", "Add New"),
+    ("find", "Find"),
+    ("save", "Save"),
+    ("search", "Search"),
+];
+
+/// Translated UI labels, the english labels are the keys.
+/// Labels are looked up in `asf_strings.txt` ("label=translation") first and then in the
+/// `strings.txt` and `tags.txt` of Athena's ASS,
+/// whose lines are matched to the english tables by their order.
+#[derive(Default, Clone, Debug)]
+pub struct Strings {
+    asf: HashMap<String, String>,
+    ass: HashMap<String, String>,
+}
+
+impl Strings {
+    pub fn load(language: Language) -> Self {
+        let mut ass = HashMap::new();
+        for file in ["strings.txt", "tags.txt"] {
+            //the english labels of asf are used as they are
+            let ass_lines = match language {
+                Language::English => Vec::new(),
+                _ => read_lines(language, file),
+            };
+            for (english, translation) in read_lines(Language::English, file)
+                .into_iter()
+                .zip(ass_lines)
+            {
+                //hotkeys are not used
+                ass.entry(english.replace('&', ""))
+                    .or_insert(translation.replace('&', ""));
+            }
+        }
+        let asf = read_lines(language, "asf_strings.txt")
+            .into_iter()
+            .filter_map(|line| {
+                line.split_once('=')
+                    .map(|(key, value)| (key.trim().to_owned(), value.trim().to_owned()))
+            })
+            .collect();
+        Strings { asf, ass }
+    }
+
+    pub fn get<'a>(&'a self, key: &'a str) -> &'a str {
+        if let Some(translation) = self.asf.get(key) {
+            return translation;
+        }
+        let ass_key = ALIASES
+            .iter()
+            .find(|(label, _)| *label == key)
+            .map_or(key, |(_, ass_key)| ass_key);
+        self.ass.get(ass_key).map_or(key, String::as_str)
+    }
+
+    /// Replaces the tokens %1, %2, ... of the translation with `args`
+    pub fn format(&self, key: &str, args: &[&dyn Display]) -> String {
+        args.iter()
+            .enumerate()
+            .rev()
+            .fold(self.get(key).to_owned(), |text, (index, arg)| {
+                text.replace(&format!("%{}", index + 1), &arg.to_string())
+            })
+    }
+}
+
+fn read_lines(language: Language, file: &str) -> Vec<String> {
+    table_entries(&language_file(language, file))
+}
+
+/// Lines of a string table without comments and empty lines
+pub fn table_entries(path: &Path) -> Vec<String> {
+    read_string(path)
+        .unwrap_or_default()
+        .lines()
+        .map(|line| line.trim_start_matches('\u{feff}').trim_end())
+        .filter(|line| !line.trim().is_empty() && !line.starts_with('#'))
+        .map(str::to_owned)
+        .collect()
+}
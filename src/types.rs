@@ -21,6 +21,17 @@ impl Language {
             Self::Japanese,
         ]
     }
+    /// Name of the folder in `./Languages` holding the translations
+    pub fn directory(&self) -> &'static str {
+        match self {
+            Self::German => "Deutsch",
+            Self::French => "Français",
+            Self::English => "English",
+            Self::Japanese => "Japanese",
+            Self::Italian => "Italiano",
+            Self::Spanish => "Español",
+        }
+    }
 }
 impl std::fmt::Display for Language {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -124,17 +135,11 @@ pub enum ArmorType {
     Arms,
     Chest,
     Waist,
-    Legs
+    Legs,
 }
 impl ArmorType {
     pub fn all() -> Vec<ArmorType> {
-        vec![
-            Self::Head,
-            Self::Arms,
-            Self::Chest,
-            Self::Waist,
-            Self::Legs
-        ]
+        vec![Self::Head, Self::Arms, Self::Chest, Self::Waist, Self::Legs]
     }
 }
 impl std::fmt::Display for ArmorType {
@@ -147,7 +152,7 @@ impl std::fmt::Display for ArmorType {
                 ArmorType::Arms => "Arms",
                 ArmorType::Chest => "Chest",
                 ArmorType::Waist => "Waist",
-                ArmorType::Legs => "Legs"
+                ArmorType::Legs => "Legs",
             }
         )
     }
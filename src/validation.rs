@@ -0,0 +1,220 @@
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+};
+
+use itertools::Itertools;
+
+use crate::{
+    app::Flags,
+    loading::{read_lines, read_skill_translation, LoadError},
+    paths::{data_file, language_dirs},
+    skillpoint::SkillPoint,
+    strings::table_entries,
+};
+
+/// Armor pieces with torso up have this instead of a skill attribute
+const TORSO_UP: &str = "胴系統倍加";
+
+/// Checks the data files and the folders in `Languages` that asf reads against the base tables.
+/// Translations are matched by line order, so every mismatch would translate to wrong names.
+pub fn validate(flags: &Flags) -> Vec<LoadError> {
+    let mut errors = unknown_skills(flags);
+    //the last attribute is the one of torso up
+    let attributes = flags
+        .skills
+        .iter()
+        .map(|skill| &skill.name_attribute)
+        .unique()
+        .count()
+        + 1;
+    let english = data_file("Languages").join("English");
+    let tags = read_lines(&english.join("tags.txt")).map_or(0, |lines| lines.len());
+    let strings = table_entries(&english.join("strings.txt")).len();
+    for language in language_dirs() {
+        for (file, expected) in [
+            ("head.txt", flags.head.len()),
+            ("body.txt", flags.body.len()),
+            ("arms.txt", flags.arms.len()),
+            ("waist.txt", flags.waist.len()),
+            ("legs.txt", flags.legs.len()),
+            ("decorations.txt", flags.decorations.len()),
+            //the first description is the one of torso up
+            ("skill_descriptions.txt", flags.skills.len() + 1),
+            ("tags.txt", tags),
+        ] {
+            check_lines(language.join(file), expected, &mut errors);
+        }
+        let path = language.join("skills.txt");
+        match read_skill_translation(&path, attributes, flags.skills.len()) {
+            //skills are looked up by their translated attribute
+            Ok((translated, _)) => errors.extend(duplicate_skills(&path, translated)),
+            Err(error) => errors.push(error),
+        }
+        let path = language.join("strings.txt");
+        let found = table_entries(&path).len();
+        if found != strings {
+            errors.push(LoadError::Lines {
+                path,
+                expected: strings,
+                found,
+            });
+        }
+    }
+    errors
+}
+
+/// Like `validate`, but also checks the translations asf doesn't use yet
+pub fn validate_all(flags: &Flags) -> Vec<LoadError> {
+    let mut errors = validate(flags);
+    for language in language_dirs() {
+        check_lines(
+            language.join("components.txt"),
+            flags.components.len(),
+            &mut errors,
+        );
+    }
+    errors
+}
+
+fn check_lines(path: PathBuf, expected: usize, errors: &mut Vec<LoadError>) {
+    match read_lines(&path) {
+        Ok(lines) if lines.len() != expected => errors.push(LoadError::Lines {
+            path,
+            expected,
+            found: lines.len(),
+        }),
+        Ok(_) => (),
+        Err(error) => errors.push(error),
+    }
+}
+
+fn duplicate_skills(path: &Path, translated: Vec<String>) -> Vec<LoadError> {
+    translated
+        .into_iter()
+        .duplicates()
+        .map(|skill| LoadError::DuplicateSkill {
+            path: path.to_path_buf(),
+            skill,
+        })
+        .collect()
+}
+
+fn unknown_skills(flags: &Flags) -> Vec<LoadError> {
+    let attributes: HashSet<&str> = flags
+        .skills
+        .iter()
+        .map(|skill| skill.name_attribute.as_str())
+        .chain([TORSO_UP])
+        .collect();
+    let mut errors = Vec::new();
+    let mut check = |file: &str, name: &str, skills: Vec<&str>| {
+        for skill in skills {
+            if !attributes.contains(skill) {
+                errors.push(LoadError::UnknownSkill {
+                    path: data_file(file),
+                    part: name.to_owned(),
+                    skill: skill.to_owned(),
+                });
+            }
+        }
+    };
+    for (file, parts) in [
+        ("head.txt", &flags.head),
+        ("body.txt", &flags.body),
+        ("arms.txt", &flags.arms),
+        ("waist.txt", &flags.waist),
+        ("legs.txt", &flags.legs),
+    ] {
+        for part in parts {
+            check(file, &part.name, part.skill_names());
+        }
+    }
+    for decoration in &flags.decorations {
+        check(
+            "decorations.txt",
+            &decoration.name,
+            decoration.skill_names(),
+        );
+    }
+    errors
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::{
+        armor::Armor, game::Game, skill::Skill, skill_id::SkillIds, skillpoint::SkillPoints,
+    };
+
+    fn flags(head: Vec<Armor>, decorations: serde_json::Value) -> Flags {
+        let skills: Vec<Skill> = ["Attack", "Guard"]
+            .map(|attribute| {
+                serde_json::from_value(json!({ "name_attribute": attribute })).unwrap()
+            })
+            .to_vec();
+        Flags {
+            components: Vec::new(),
+            body: Vec::new(),
+            head,
+            arms: Vec::new(),
+            waist: Vec::new(),
+            legs: Vec::new(),
+            relic_body: Vec::new(),
+            relic_head: Vec::new(),
+            relic_arms: Vec::new(),
+            relic_waist: Vec::new(),
+            relic_legs: Vec::new(),
+            charms: Vec::new(),
+            decorations: serde_json::from_value(decorations).unwrap(),
+            skill_ids: SkillIds::new(&skills),
+            skills,
+            game: Game::default(),
+        }
+    }
+
+    #[test]
+    fn unknown_skills_name_the_part() {
+        let helm = |name: &str, skill: &str| Armor {
+            name: name.to_owned(),
+            skills: vec![SkillPoints::new("Attack", 2), SkillPoints::new(skill, 1)],
+            ..Default::default()
+        };
+        let flags = flags(
+            vec![helm("Helm", TORSO_UP), helm("Cap", "Atack")],
+            json!([{"name": "Jewel", "skill_1_name": "Gaurd", "skill_1_points": 1}]),
+        );
+        let errors = unknown_skills(&flags)
+            .into_iter()
+            .map(|error| match error {
+                LoadError::UnknownSkill { path, part, skill } => (path, part, skill),
+                error => panic!("{error}"),
+            })
+            .collect_vec();
+        assert_eq!(
+            errors,
+            [
+                (data_file("head.txt"), "Cap".to_owned(), "Atack".to_owned()),
+                (
+                    data_file("decorations.txt"),
+                    "Jewel".to_owned(),
+                    "Gaurd".to_owned()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn translations_with_the_same_name_for_two_skills_are_reported() {
+        let path = Path::new("skills.txt");
+        let translated = ["Angriff", "Schutz", "Angriff"].map(str::to_owned).to_vec();
+        let errors = duplicate_skills(path, translated);
+        assert!(matches!(
+            &errors[..],
+            [LoadError::DuplicateSkill { skill, .. }] if skill == "Angriff"
+        ));
+        assert!(duplicate_skills(path, vec!["Angriff".to_owned()]).is_empty());
+    }
+}